@@ -0,0 +1,101 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+/// Where cached answers are kept (relative to the working directory like the `./inputs/` files)
+const DEFAULT_CACHE_DIR: &str = "./.answer_cache";
+
+/// Identifies a single cached answer
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct CacheKey<'a> {
+    pub day: &'a str,
+    pub part: u8,
+    pub input_hash: u64,
+    pub solver_version: Option<&'a str>,
+}
+
+impl<'a> CacheKey<'a> {
+    pub fn new(day: &'a str, part: u8, input: &[u8], solver_version: Option<&'a str>) -> Self {
+        CacheKey {
+            day,
+            part,
+            input_hash: hash_input(input),
+            solver_version,
+        }
+    }
+}
+
+/// A local on-disk store of previously computed answers
+///
+/// Each answer lives in its own file at `<dir>/<day>/part<part>-<input hash>.txt`. The first line
+/// of the file records the solver version the answer was computed with, so bumping the version
+/// makes the old entry a miss (and it gets overwritten on the next store).
+pub struct AnswerCache {
+    dir: PathBuf,
+}
+
+impl AnswerCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        AnswerCache { dir: dir.into() }
+    }
+
+    fn entry_path(&self, key: &CacheKey) -> PathBuf {
+        self.dir
+            .join(key.day)
+            .join(format!("part{}-{:016x}.txt", key.part, key.input_hash))
+    }
+
+    pub fn get(&self, key: &CacheKey) -> Option<String> {
+        let contents = fs::read_to_string(self.entry_path(key)).ok()?;
+        let (version, answer) = contents.split_once('\n')?;
+        if version != key.solver_version.unwrap_or("") {
+            return None;
+        }
+        Some(answer.to_string())
+    }
+
+    pub fn store(&self, key: &CacheKey, answer: &str) -> io::Result<()> {
+        let path = self.entry_path(key);
+        fs::create_dir_all(path.parent().unwrap())?;
+        fs::write(path, format!("{}\n{answer}", key.solver_version.unwrap_or("")))
+    }
+}
+
+impl Default for AnswerCache {
+    fn default() -> Self {
+        AnswerCache::new(DEFAULT_CACHE_DIR)
+    }
+}
+
+/// A 64-bit FNV-1a hash of the input
+///
+/// The standard library's hasher isn't guaranteed to be stable between releases, which would
+/// silently invalidate the whole cache on a toolchain update.
+pub fn hash_input(input: &[u8]) -> u64 {
+    input.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[test]
+fn test_hash_input() {
+    // Reference values for FNV-1a 64
+    assert_eq!(0xcbf29ce484222325, hash_input(b""));
+    assert_eq!(0xaf63dc4c8601ec8c, hash_input(b"a"));
+}
+
+#[test]
+fn test_store_and_get() {
+    let cache = AnswerCache::new(std::env::temp_dir().join(format!("answer_cache_test_{}", std::process::id())));
+    let key = CacheKey::new("00_test", 1, b"3   4", Some("v1"));
+
+    assert_eq!(None, cache.get(&key));
+    cache.store(&key, "11").unwrap();
+    assert_eq!(Some("11".to_string()), cache.get(&key));
+
+    // A different solver version or input is a miss
+    assert_eq!(None, cache.get(&CacheKey::new("00_test", 1, b"3   4", Some("v2"))));
+    assert_eq!(None, cache.get(&CacheKey::new("00_test", 1, b"3   5", Some("v1"))));
+
+    fs::remove_dir_all(&cache.dir).unwrap();
+}
@@ -125,38 +125,81 @@ Once again consider your left and right lists. What is their similarity
 score?
  */
 
-use std::fs;
+use advent_of_code2024::runner::Runner;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap};
+use std::env;
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, ErrorKind, Read, Write};
+use std::path::PathBuf;
+use std::process;
 use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use itertools::Itertools;
 
 fn main() {
-    let input_file = fs::read_to_string("./inputs/01_historian_hysteria.txt").unwrap();
-    let input_lines = input_file.lines();
-    println!("{}", get_distance_score(input_lines.clone()));
-    println!("{}", get_similarity_score(input_lines));
+    let runner = Runner::from_args("01_historian_hysteria").solver_version("2");
+    if runner.has_flag("--external-memory") {
+        // Never hold the lists in memory: sort them into runs on disk and merge those instead
+        let run_size = runner.parsed_option("--run-size").unwrap_or(DEFAULT_RUN_SIZE);
+        let (left, right) = extract_columns_external(runner.input_lines(), run_size);
+        println!("{}", distance_score_sorted(left.sorted(), right.sorted()));
+        println!("{}", similarity_score_sorted(left.sorted(), right.sorted()));
+        return;
+    }
+    if runner.has_flag("--report") || runner.option("--report").is_some() {
+        let top_n = runner.parsed_option("--top").unwrap_or(10);
+        let report = get_reconciliation_report(runner.input_lines(), top_n);
+        if runner.option("--report") == Some("csv") {
+            print!("{}", report.to_csv());
+        } else {
+            print!("{}", report.to_text());
+        }
+        return;
+    }
+    runner.run_parts(
+        |input| get_distance_score(input.lines()),
+        |input| get_similarity_score(input.lines()),
+        |runner| {
+            let (left, right) = extract_columns(runner.input_lines());
+            let similarity_score = similarity_score(&left, &right);
+            (distance_score(left, right), similarity_score)
+        }
+    );
 }
 
-fn extract_columns<'a>(input_lines: impl Iterator<Item=&'a str>) -> (Vec<u64>, Vec<u64>) {
+fn extract_columns_into(input_lines: impl Iterator<Item=impl AsRef<str>>, left: &mut impl Extend<u64>, right: &mut impl Extend<u64>) {
     // Get the two columns out of the input
-    let mut left = Vec::new();
-    let mut right = Vec::new();
     for line in input_lines {
-        let parts = line.split_whitespace().collect_vec();
-        left.push(u64::from_str(parts[0]).unwrap());
-        right.push(u64::from_str(parts[1]).unwrap());
+        let parts = line.as_ref().split_whitespace().collect_vec();
+        left.extend([u64::from_str(parts[0]).unwrap()]);
+        right.extend([u64::from_str(parts[1]).unwrap()]);
     }
+}
+
+fn extract_columns(input_lines: impl Iterator<Item=impl AsRef<str>>) -> (Vec<u64>, Vec<u64>) {
+    let mut left = Vec::new();
+    let mut right = Vec::new();
+    extract_columns_into(input_lines, &mut left, &mut right);
     (left, right)
 }
 
 fn get_distance_score<'a>(input_lines: impl Iterator<Item=&'a str>) -> u64 {
-    let (mut left, mut right) = extract_columns(input_lines);
+    let (left, right) = extract_columns(input_lines);
+    distance_score(left, right)
+}
 
+fn distance_score(mut left: Vec<u64>, mut right: Vec<u64>) -> u64 {
     // Sort them
     left.sort();
     right.sort();
 
+    distance_score_sorted(left.into_iter(), right.into_iter())
+}
+
+fn distance_score_sorted(left: impl Iterator<Item=u64>, right: impl Iterator<Item=u64>) -> u64 {
     // Zip them together
-    left.into_iter().zip(right)
+    left.zip(right)
         // Get the absolute difference
         .map(|(left, right)| left.abs_diff(right))
         // Sum the differences
@@ -165,17 +208,238 @@ fn get_distance_score<'a>(input_lines: impl Iterator<Item=&'a str>) -> u64 {
 
 fn get_similarity_score<'a>(input_lines: impl Iterator<Item=&'a str>) -> u64 {
     let (left, right) = extract_columns(input_lines);
+    similarity_score(&left, &right)
+}
 
+fn similarity_score(left: &[u64], right: &[u64]) -> u64 {
     // Get the times each number appears in the right list
-    let right = right.into_iter().counts();
+    let right = right.iter().counts();
 
-    left.into_iter()
+    left.iter()
         // Multiply the left entry by the count of times it appears in the right list or 0 if it doesn't
-        .map(|left| left * (*right.get(&left).unwrap_or(&0)) as u64)
+        .map(|left| left * (*right.get(left).unwrap_or(&0)) as u64)
         // Sum them all up
         .sum()
 }
 
+/// Same as [similarity_score] but for sorted lists, so that it can be done as a merge-join
+fn similarity_score_sorted(left: impl Iterator<Item=u64>, right: impl Iterator<Item=u64>) -> u64 {
+    let mut right = right.peekable();
+    let mut score = 0;
+    for (left_count, left) in left.dedup_with_count() {
+        // Skip past everything smaller in the right list then count the matches
+        while right.next_if(|right| *right < left).is_some() {}
+        let mut right_count = 0;
+        while right.next_if_eq(&left).is_some() {
+            right_count += 1;
+        }
+        score += left * (left_count as u64) * right_count;
+    }
+    score
+}
+
+/// Which location IDs the two lists disagree on
+#[derive(Debug, PartialEq)]
+struct ReconciliationReport {
+    /// Location IDs (and how many times they appear) which are only in the left list
+    only_left: Vec<(u64, usize)>,
+    /// Location IDs (and how many times they appear) which are only in the right list
+    only_right: Vec<(u64, usize)>,
+    /// Location IDs in both lists but not the same number of times: (ID, left count, right count)
+    differing_counts: Vec<(u64, usize, usize)>,
+    /// The pairs from the sorted pairing which are furthest apart, furthest first
+    largest_distances: Vec<(u64, u64)>,
+}
+
+impl ReconciliationReport {
+    fn to_text(&self) -> String {
+        let mut result = String::new();
+        result.push_str(&format!("Only in the left list ({}):\n", self.only_left.len()));
+        for (location_id, count) in &self.only_left {
+            result.push_str(&format!("  {location_id} x{count}\n"));
+        }
+        result.push_str(&format!("Only in the right list ({}):\n", self.only_right.len()));
+        for (location_id, count) in &self.only_right {
+            result.push_str(&format!("  {location_id} x{count}\n"));
+        }
+        result.push_str(&format!("In both lists a different number of times ({}):\n", self.differing_counts.len()));
+        for (location_id, left_count, right_count) in &self.differing_counts {
+            result.push_str(&format!("  {location_id} x{left_count} left, x{right_count} right\n"));
+        }
+        result.push_str(&format!("Largest paired distances ({}):\n", self.largest_distances.len()));
+        for (left, right) in &self.largest_distances {
+            result.push_str(&format!("  {left} - {right} = {}\n", left.abs_diff(*right)));
+        }
+        result
+    }
+
+    fn to_csv(&self) -> String {
+        let mut result = String::from("category,left_id,right_id,left_count,right_count,distance\n");
+        for (location_id, count) in &self.only_left {
+            result.push_str(&format!("only_left,{location_id},,{count},0,\n"));
+        }
+        for (location_id, count) in &self.only_right {
+            result.push_str(&format!("only_right,,{location_id},0,{count},\n"));
+        }
+        for (location_id, left_count, right_count) in &self.differing_counts {
+            result.push_str(&format!("differing_count,{location_id},{location_id},{left_count},{right_count},\n"));
+        }
+        for (left, right) in &self.largest_distances {
+            result.push_str(&format!("largest_distance,{left},{right},,,{}\n", left.abs_diff(*right)));
+        }
+        result
+    }
+}
+
+fn get_reconciliation_report(input_lines: impl Iterator<Item=impl AsRef<str>>, top_n: usize) -> ReconciliationReport {
+    let (mut left, mut right) = extract_columns(input_lines);
+
+    // Line the counts for each location ID up side by side
+    let mut counts: BTreeMap<u64, (usize, usize)> = BTreeMap::new();
+    for location_id in &left {
+        counts.entry(*location_id).or_default().0 += 1;
+    }
+    for location_id in &right {
+        counts.entry(*location_id).or_default().1 += 1;
+    }
+
+    let mut report = ReconciliationReport {
+        only_left: vec![],
+        only_right: vec![],
+        differing_counts: vec![],
+        largest_distances: vec![],
+    };
+    for (location_id, (left_count, right_count)) in counts {
+        if right_count == 0 {
+            report.only_left.push((location_id, left_count));
+        } else if left_count == 0 {
+            report.only_right.push((location_id, right_count));
+        } else if left_count != right_count {
+            report.differing_counts.push((location_id, left_count, right_count));
+        }
+    }
+
+    // Pair them up the same way as the distance score does
+    left.sort();
+    right.sort();
+    report.largest_distances = left.into_iter().zip(right)
+        .sorted_by_key(|(left, right)| Reverse(left.abs_diff(*right)))
+        .take(top_n)
+        .collect_vec();
+
+    report
+}
+
+/// How many location IDs are sorted in memory at once before being written out as a run
+const DEFAULT_RUN_SIZE: usize = 1 << 20;
+
+/// Distinguishes the runs of different columns (and of concurrent tests)
+static NEXT_COLUMN_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// A column of location IDs kept on disk as sorted runs, which are deleted when this is dropped
+struct ExternalColumn {
+    id: usize,
+    run_size: usize,
+    buffer: Vec<u64>,
+    runs: Vec<PathBuf>,
+}
+
+impl ExternalColumn {
+    fn new(run_size: usize) -> Self {
+        ExternalColumn {
+            id: NEXT_COLUMN_ID.fetch_add(1, Ordering::Relaxed),
+            run_size,
+            buffer: Vec::with_capacity(run_size),
+            runs: vec![],
+        }
+    }
+
+    /// Sorts whatever is buffered and writes it out as a new run
+    fn flush_run(&mut self) {
+        if self.buffer.is_empty() {
+            return;
+        }
+        self.buffer.sort();
+
+        let path = env::temp_dir().join(format!("historian_hysteria_{}_{}_{}.bin", process::id(), self.id, self.runs.len()));
+        let mut writer = BufWriter::new(File::create(&path).unwrap());
+        for location_id in self.buffer.drain(..) {
+            writer.write_all(&location_id.to_le_bytes()).unwrap();
+        }
+        writer.flush().unwrap();
+        self.runs.push(path);
+    }
+
+    /// Merges the runs back together in sorted order
+    fn sorted(&self) -> MergedRuns {
+        let mut readers = self.runs.iter()
+            .map(|path| BufReader::new(File::open(path).unwrap()))
+            .collect_vec();
+        let mut heap = BinaryHeap::new();
+        for (run, reader) in readers.iter_mut().enumerate() {
+            if let Some(location_id) = read_location_id(reader) {
+                heap.push(Reverse((location_id, run)));
+            }
+        }
+        MergedRuns { readers, heap }
+    }
+}
+
+impl Extend<u64> for ExternalColumn {
+    fn extend<T: IntoIterator<Item=u64>>(&mut self, iter: T) {
+        for location_id in iter {
+            self.buffer.push(location_id);
+            if self.buffer.len() >= self.run_size {
+                self.flush_run();
+            }
+        }
+    }
+}
+
+impl Drop for ExternalColumn {
+    fn drop(&mut self) {
+        for run in &self.runs {
+            let _ = fs::remove_file(run);
+        }
+    }
+}
+
+fn read_location_id(reader: &mut impl Read) -> Option<u64> {
+    let mut bytes = [0u8; 8];
+    match reader.read_exact(&mut bytes) {
+        Ok(()) => Some(u64::from_le_bytes(bytes)),
+        Err(e) if e.kind() == ErrorKind::UnexpectedEof => None,
+        Err(e) => panic!("Couldn't read run: {e}"),
+    }
+}
+
+/// A k-way merge of sorted runs
+struct MergedRuns {
+    readers: Vec<BufReader<File>>,
+    heap: BinaryHeap<Reverse<(u64, usize)>>,
+}
+
+impl Iterator for MergedRuns {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        let Reverse((location_id, run)) = self.heap.pop()?;
+        if let Some(next_location_id) = read_location_id(&mut self.readers[run]) {
+            self.heap.push(Reverse((next_location_id, run)));
+        }
+        Some(location_id)
+    }
+}
+
+fn extract_columns_external(input_lines: impl Iterator<Item=impl AsRef<str>>, run_size: usize) -> (ExternalColumn, ExternalColumn) {
+    let mut left = ExternalColumn::new(run_size);
+    let mut right = ExternalColumn::new(run_size);
+    extract_columns_into(input_lines, &mut left, &mut right);
+    left.flush_run();
+    right.flush_run();
+    (left, right)
+}
+
 #[test]
 fn test_part1() {
     assert_eq!(11,
@@ -203,3 +467,41 @@ fn test_part2() {
                )
     )
 }
+
+#[test]
+fn test_external_memory() {
+    let input = r"3   4
+                    4   3
+                    2   5
+                    1   3
+                    3   9
+                    3   3";
+    // A run size of 2 splits each column over three runs
+    let (left, right) = extract_columns_external(input.lines(), 2);
+    assert_eq!(3, left.runs.len());
+    assert_eq!(vec![1, 2, 3, 3, 3, 4], left.sorted().collect_vec());
+    assert_eq!(11, distance_score_sorted(left.sorted(), right.sorted()));
+    assert_eq!(31, similarity_score_sorted(left.sorted(), right.sorted()));
+}
+
+#[test]
+fn test_reconciliation_report() {
+    let report = get_reconciliation_report(
+        r"3   4
+                    4   3
+                    2   5
+                    1   3
+                    3   9
+                    3   3".lines(),
+        2
+    );
+    assert_eq!(
+        ReconciliationReport {
+            only_left: vec![(1, 1), (2, 1)],
+            only_right: vec![(5, 1), (9, 1)],
+            differing_counts: vec![],
+            largest_distances: vec![(4, 9), (1, 3)],
+        },
+        report
+    );
+}
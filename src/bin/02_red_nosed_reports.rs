@@ -75,15 +75,48 @@ Update your analysis by handling situations where the Problem Dampener can
 remove a single level from unsafe reports. How many reports are now safe?
  */
 
-use std::fs;
+use advent_of_code2024::runner::Runner;
 use std::str::FromStr;
 use itertools::Itertools;
 
 fn main() {
-    let input_file = fs::read_to_string("./inputs/02_red_nosed_reports.txt").unwrap();
-    let input_lines = input_file.lines();
-    println!("{}", get_safe_report_count(input_lines.clone(), is_report_safe));
-    println!("{}", get_safe_report_count(input_lines.clone(), is_report_safe_problem_dampener_brute_force));
+    let runner = Runner::from_args("02_red_nosed_reports").solver_version("2");
+    let rules = SafetyRules {
+        min_step: runner.parsed_option("--min-step").unwrap_or(1),
+        max_step: runner.parsed_option("--max-step").unwrap_or(3),
+        direction: match runner.option("--direction") {
+            Some("increasing") => ChangeDirection::Increasing,
+            Some("decreasing") => ChangeDirection::Decreasing,
+            _ => ChangeDirection::Unknown,
+        },
+    };
+    if runner.has_flag("--diagnose") {
+        for line in runner.input_lines() {
+            let report = parse_report(&line);
+            println!("{}: {}", line.trim(), describe_diagnosis(&report, &diagnose_report(&report, &rules)));
+        }
+        return;
+    }
+    if let Some(max_removals) = runner.parsed_option("--max-removals") {
+        println!("{}", get_safe_report_count(
+            runner.input_lines(),
+            |report| find_levels_to_remove(report, max_removals, &rules).is_some()
+        ));
+        return;
+    }
+    if rules != SafetyRules::default() {
+        // The answers depend on the rules as well as the input, so can't be cached
+        let (safe_count, safe_count_problem_dampener) = get_safe_report_counts(runner.input_lines(), &rules);
+        println!("{safe_count}");
+        println!("{safe_count_problem_dampener}");
+        return;
+    }
+
+    runner.run_parts(
+        |input| get_safe_report_count(input.lines(), is_report_safe),
+        |input| get_safe_report_count(input.lines(), is_report_safe_problem_dampener),
+        |runner| get_safe_report_counts(runner.input_lines(), &rules)
+    );
 }
 
 /// A tri-state enum to represent the direction of a sequence
@@ -95,7 +128,7 @@ enum ChangeDirection {
 }
 
 /// A simple solution which only works for part 1
-fn is_report_safe(report: &Vec<u64>) -> bool {
+fn is_report_safe(report: &[u64]) -> bool {
     let mut direction = ChangeDirection::Unknown;
 
     for adjacent_pair in report.windows(2) {
@@ -121,8 +154,9 @@ fn is_report_safe(report: &Vec<u64>) -> bool {
 }
 
 /// An extension to the simple solution that allows for part 2 to be solved (by brute force)
-fn is_report_safe_problem_dampener_brute_force(report: &Vec<u64>) -> bool {
-    if is_report_safe(&report) {
+#[allow(dead_code)] // Kept to check is_report_safe_problem_dampener against
+fn is_report_safe_problem_dampener_brute_force(report: &[u64]) -> bool {
+    if is_report_safe(report) {
         return true;
     }
 
@@ -139,19 +173,197 @@ fn is_report_safe_problem_dampener_brute_force(report: &Vec<u64>) -> bool {
     false
 }
 
-fn get_safe_report_count<'a>(input_lines: impl Iterator<Item=&'a str>, report_safe_fn: impl Fn(&Vec<u64>)->bool) -> u64 {
-    let reports = input_lines.into_iter()
-        .map(|report|
-            report.split_whitespace()
-                .map(|level| u64::from_str(level).unwrap())
-                .collect_vec()
-        ).collect_vec();
+/// The rules a report has to follow to be considered safe
+#[derive(PartialEq)]
+struct SafetyRules {
+    /// The smallest allowed difference between adjacent levels
+    min_step: u64,
+    /// The largest allowed difference between adjacent levels
+    max_step: u64,
+    /// Which way the levels have to go, [ChangeDirection::Unknown] allows either (but not both)
+    direction: ChangeDirection,
+}
+
+impl Default for SafetyRules {
+    fn default() -> Self {
+        SafetyRules {
+            min_step: 1,
+            max_step: 3,
+            direction: ChangeDirection::Unknown,
+        }
+    }
+}
+
+/// Finds the fewest levels to remove (at most `max_removals`) to make the report safe
+///
+/// Returns the indices of the levels to remove, or None if more than `max_removals` would need to
+/// be removed. For each direction this is a dynamic program over which level was kept last: the
+/// fewest removals for a safe prefix ending by keeping level `i` only depends on the previous
+/// kept level, which has to be one of the `max_removals + 1` levels before `i`. So this runs in
+/// O(n * k) rather than trying every combination of removals.
+fn find_levels_to_remove(report: &[u64], max_removals: usize, rules: &SafetyRules) -> Option<Vec<usize>> {
+    if report.is_empty() {
+        return Some(vec![]);
+    }
+
+    let directions = match rules.direction {
+        ChangeDirection::Unknown => vec![ChangeDirection::Increasing, ChangeDirection::Decreasing],
+        direction => vec![direction],
+    };
+
+    directions.into_iter()
+        .filter_map(|direction| {
+            let is_allowed_step = |first: u64, second: u64| {
+                let difference = first.abs_diff(second);
+                let step_direction = if first < second { ChangeDirection::Increasing } else { ChangeDirection::Decreasing };
+                difference >= rules.min_step && difference <= rules.max_step && step_direction == direction
+            };
+
+            // removals[i] is the fewest removals to make report[..=i] safe while keeping level i,
+            // previous_kept[i] is the kept level before i in that solution
+            let mut removals = vec![usize::MAX; report.len()];
+            let mut previous_kept = vec![None; report.len()];
+            for i in 0..report.len() {
+                // Keeping level i as the first level means removing everything before it
+                removals[i] = i;
+                for previous in (i.saturating_sub(max_removals + 1)..i).rev() {
+                    if removals[previous] == usize::MAX || !is_allowed_step(report[previous], report[i]) {
+                        continue;
+                    }
+                    let candidate = removals[previous] + (i - previous - 1);
+                    if candidate < removals[i] {
+                        removals[i] = candidate;
+                        previous_kept[i] = Some(previous);
+                    }
+                }
+            }
+
+            // The last kept level means removing everything after it
+            let (last_kept, total_removals) = (0..report.len())
+                .map(|i| (i, removals[i].saturating_add(report.len() - 1 - i)))
+                .min_by_key(|(_, total_removals)| *total_removals)?;
+            if total_removals > max_removals {
+                return None;
+            }
+
+            // Walk back through the kept levels, anything not kept was removed
+            let mut kept = vec![false; report.len()];
+            let mut current = Some(last_kept);
+            while let Some(i) = current {
+                kept[i] = true;
+                current = previous_kept[i];
+            }
+            Some((0..report.len()).filter(|i| !kept[*i]).collect_vec())
+        })
+        .min_by_key(|to_remove| to_remove.len())
+}
+
+/// The Problem Dampener using [find_levels_to_remove], rather than retrying every removal
+fn is_report_safe_problem_dampener(report: &[u64]) -> bool {
+    find_levels_to_remove(report, 1, &SafetyRules::default()).is_some()
+}
+
+/// Why a pair of adjacent levels breaks the rules
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+enum Violation {
+    /// The levels differ by too little or too much
+    StepSize { difference: u64 },
+    /// The levels go the other way to the report (or the way the rules don't allow)
+    DirectionChange,
+}
+
+/// Whether a report is safe once the Problem Dampener has had a go at it
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+enum Verdict {
+    Safe,
+    /// Safe once the level at this index is removed
+    SafeWithRemoval(usize),
+    Unsafe,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+struct ReportDiagnosis {
+    /// The index of the first level of the first adjacent pair which breaks the rules
+    first_violation: Option<(usize, Violation)>,
+    verdict: Verdict,
+}
+
+fn diagnose_report(report: &[u64], rules: &SafetyRules) -> ReportDiagnosis {
+    let mut direction = rules.direction;
+    let mut first_violation = None;
+    for (i, adjacent_pair) in report.windows(2).enumerate() {
+        let first = adjacent_pair[0];
+        let second = adjacent_pair[1];
+        let difference = first.abs_diff(second);
+
+        if difference < rules.min_step || difference > rules.max_step {
+            first_violation = Some((i, Violation::StepSize { difference }));
+            break;
+        }
+
+        let pair_direction = if first < second { ChangeDirection::Increasing } else { ChangeDirection::Decreasing };
+        if direction != ChangeDirection::Unknown && pair_direction != direction {
+            first_violation = Some((i, Violation::DirectionChange));
+            break;
+        }
+        direction = pair_direction;
+    }
+
+    let verdict = match find_levels_to_remove(report, 1, rules).as_deref() {
+        Some([]) => Verdict::Safe,
+        Some([removed]) => Verdict::SafeWithRemoval(*removed),
+        _ => Verdict::Unsafe,
+    };
 
-    reports.into_iter()
-        .filter(|report| report_safe_fn(&report))
+    ReportDiagnosis {
+        first_violation,
+        verdict,
+    }
+}
+
+fn describe_diagnosis(report: &[u64], diagnosis: &ReportDiagnosis) -> String {
+    let verdict = match diagnosis.verdict {
+        Verdict::Safe => "safe".to_string(),
+        Verdict::SafeWithRemoval(removed) => format!("safe by removing level {removed} ({})", report[removed]),
+        Verdict::Unsafe => "unsafe".to_string(),
+    };
+    match diagnosis.first_violation {
+        None => verdict,
+        Some((i, violation)) => {
+            let reason = match violation {
+                Violation::StepSize { difference } => format!("change by {difference}"),
+                Violation::DirectionChange => "change direction".to_string(),
+            };
+            format!("levels {i}-{} ({} {}) {reason}, {verdict}", i + 1, report[i], report[i + 1])
+        }
+    }
+}
+
+fn parse_report(report: &str) -> Vec<u64> {
+    report.split_whitespace()
+        .map(|level| u64::from_str(level).unwrap())
+        .collect_vec()
+}
+
+fn get_safe_report_count(input_lines: impl Iterator<Item=impl AsRef<str>>, report_safe_fn: impl Fn(&[u64])->bool) -> u64 {
+    input_lines
+        .map(|report| parse_report(report.as_ref()))
+        .filter(|report| report_safe_fn(report))
         .count() as u64
 }
 
+/// Counts the safe reports without and with the Problem Dampener in a single pass over the input
+fn get_safe_report_counts(input_lines: impl Iterator<Item=impl AsRef<str>>, rules: &SafetyRules) -> (u64, u64) {
+    input_lines
+        .map(|report| parse_report(report.as_ref()))
+        .fold((0, 0), |(safe_count, safe_count_problem_dampener), report| {
+            (
+                safe_count + find_levels_to_remove(&report, 0, rules).is_some() as u64,
+                safe_count_problem_dampener + find_levels_to_remove(&report, 1, rules).is_some() as u64,
+            )
+        })
+}
+
 #[test]
 fn test_part1() {
     assert_eq!(2,
@@ -181,3 +393,98 @@ fn test_part2() {
                )
     );
 }
+
+#[test]
+fn test_part2_dynamic_programming() {
+    assert_eq!(4,
+               get_safe_report_count(
+                   r"7 6 4 2 1
+                    1 2 7 8 9
+                    9 7 6 2 1
+                    1 3 2 4 5
+                    8 6 4 4 1
+                    1 3 6 7 9".lines(),
+                   is_report_safe_problem_dampener
+               )
+    );
+}
+
+#[test]
+fn test_safe_report_counts() {
+    assert_eq!((2, 4),
+               get_safe_report_counts(
+                   r"7 6 4 2 1
+                    1 2 7 8 9
+                    9 7 6 2 1
+                    1 3 2 4 5
+                    8 6 4 4 1
+                    1 3 6 7 9".lines(),
+                   &SafetyRules::default()
+               )
+    );
+
+    // Allowing bigger steps makes 1 2 7 8 9 and 9 7 6 2 1 safe as they are
+    assert_eq!((4, 6),
+               get_safe_report_counts(
+                   r"7 6 4 2 1
+                    1 2 7 8 9
+                    9 7 6 2 1
+                    1 3 2 4 5
+                    8 6 4 4 1
+                    1 3 6 7 9".lines(),
+                   &SafetyRules { max_step: 5, ..SafetyRules::default() }
+               )
+    );
+}
+
+#[test]
+fn test_find_levels_to_remove() {
+    let rules = SafetyRules::default();
+    assert_eq!(Some(vec![]), find_levels_to_remove(&[7, 6, 4, 2, 1], 1, &rules));
+    assert_eq!(None, find_levels_to_remove(&[1, 2, 7, 8, 9], 1, &rules));
+    assert_eq!(Some(vec![1]), find_levels_to_remove(&[1, 3, 2, 4, 5], 1, &rules));
+    assert_eq!(Some(vec![2]), find_levels_to_remove(&[8, 6, 4, 4, 1], 1, &rules));
+    assert_eq!(None, find_levels_to_remove(&[1, 3, 2, 4, 5], 0, &rules));
+
+    // Removing the first two levels is enough with a big enough dampener
+    assert_eq!(Some(vec![0, 1]), find_levels_to_remove(&[1, 2, 7, 8, 9], 3, &rules));
+
+    // With only increases allowed the first report can't be saved
+    let increasing_rules = SafetyRules {
+        direction: ChangeDirection::Increasing,
+        ..SafetyRules::default()
+    };
+    assert_eq!(None, find_levels_to_remove(&[7, 6, 4, 2, 1], 2, &increasing_rules));
+
+    // Wider steps make the jump from 2 to 7 fine
+    let wide_rules = SafetyRules {
+        max_step: 5,
+        ..SafetyRules::default()
+    };
+    assert_eq!(Some(vec![]), find_levels_to_remove(&[1, 2, 7, 8, 9], 0, &wide_rules));
+}
+
+#[test]
+fn test_diagnose_report() {
+    let rules = SafetyRules::default();
+    assert_eq!(
+        ReportDiagnosis { first_violation: None, verdict: Verdict::Safe },
+        diagnose_report(&[7, 6, 4, 2, 1], &rules)
+    );
+    assert_eq!(
+        ReportDiagnosis { first_violation: Some((1, Violation::StepSize { difference: 5 })), verdict: Verdict::Unsafe },
+        diagnose_report(&[1, 2, 7, 8, 9], &rules)
+    );
+    assert_eq!(
+        ReportDiagnosis { first_violation: Some((1, Violation::DirectionChange)), verdict: Verdict::SafeWithRemoval(1) },
+        diagnose_report(&[1, 3, 2, 4, 5], &rules)
+    );
+    assert_eq!(
+        ReportDiagnosis { first_violation: Some((2, Violation::StepSize { difference: 0 })), verdict: Verdict::SafeWithRemoval(2) },
+        diagnose_report(&[8, 6, 4, 4, 1], &rules)
+    );
+    assert_eq!(
+        "levels 1-2 (3 2) change direction, safe by removing level 1 (3)",
+        describe_diagnosis(&[1, 3, 2, 4, 5], &diagnose_report(&[1, 3, 2, 4, 5], &rules))
+    );
+}
@@ -63,56 +63,323 @@ Handle the new instructions; what do you get if you add up all of the
 results of just the enabled multiplications?
  */
 
-use std::fs;
-use std::str::FromStr;
-use regex::{Captures, Regex};
+use advent_of_code2024::runner::Runner;
+use std::fmt::{Display, Formatter};
+use std::io::{self, Read};
+use itertools::Itertools;
 
 fn main() {
-    let input_file = fs::read_to_string("./inputs/03_mull_it_over.txt").unwrap();
-    println!("{}", get_multiplication_sum(&input_file));
-    println!("{}", get_multiplication_sum_conditional(&input_file));
+    let runner = Runner::from_args("03_mull_it_over").solver_version("2");
+    if runner.has_flag("--trace") || runner.has_flag("--extended") {
+        let instructions = if runner.has_flag("--extended") { EXTENDED_INSTRUCTIONS } else { CONDITIONAL_INSTRUCTIONS };
+        let is_traced = runner.has_flag("--trace");
+        let mut machine = Machine::default();
+        run_program(runner.read_input().as_bytes(), instructions, &mut machine, |call| if is_traced {
+            println!("{call}");
+        });
+        println!("{}", machine.sum.expect(OVERFLOW_MESSAGE));
+        return;
+    }
+
+    let chunk_size = runner.parsed_option("--chunk-size").unwrap_or(DEFAULT_CHUNK_SIZE);
+    runner.run_parts(
+        get_multiplication_sum,
+        get_multiplication_sum_conditional,
+        |runner| get_multiplication_sums(runner.input_reader(), chunk_size).unwrap()
+    );
+}
+
+fn get_multiplication_sum(input: &str) -> u64 {
+    get_program_sum(input.as_bytes(), BASIC_INSTRUCTIONS)
 }
 
-fn get_mul_regex() -> Regex {
-    Regex::new(r"mul\((\d{1,3}),(\d{1,3})\)").unwrap()
+fn get_multiplication_sum_conditional(input: &str) -> u64 {
+    get_program_sum(input.as_bytes(), CONDITIONAL_INSTRUCTIONS)
 }
 
-fn handle_mul_capture(capture: Captures) -> u64 {
-    u64::from_str(capture.get(1).unwrap().as_str()).unwrap() * u64::from_str(capture.get(2).unwrap().as_str()).unwrap()
+/// Runs instructions from memory which arrives a chunk at a time
+///
+/// Any bytes at the end of a chunk which could be the start of an instruction are held back and
+/// scanned again once the next chunk has arrived.
+struct StreamScanner<'a> {
+    instructions: &'a [Instruction],
+    machine: Machine,
+    pending: Vec<u8>,
+    /// The byte offset of the start of `pending`
+    offset: usize,
 }
 
-fn get_multiplication_sum(input: &str) -> u64 {
-    let mul_pattern = get_mul_regex();
+impl<'a> StreamScanner<'a> {
+    fn new(instructions: &'a [Instruction]) -> Self {
+        StreamScanner {
+            instructions,
+            machine: Machine::default(),
+            pending: vec![],
+            offset: 0,
+        }
+    }
+
+    fn feed(&mut self, chunk: &[u8], on_execute: &mut impl FnMut(&Call)) {
+        self.pending.extend_from_slice(chunk);
+        let held_back = run_memory(&self.pending, self.offset, self.instructions, &mut self.machine, on_execute);
+        let scanned = self.pending.len() - held_back;
+        self.pending.drain(..scanned);
+        self.offset += scanned;
+    }
 
-    mul_pattern.captures_iter(input)
-        .map(handle_mul_capture)
-        .sum()
+    /// The sum once all of the memory has been fed in, anything still held back was corrupted
+    fn finish(self) -> u64 {
+        self.machine.sum.expect(OVERFLOW_MESSAGE)
+    }
 }
 
-fn get_multiplication_sum_conditional(input: &str) -> u64 {
-    let mul_pattern = get_mul_regex().as_str().to_string();
-    let do_dont_pattern = Regex::new(r"do\(\)|don't\(\)").unwrap().as_str().to_string();
-    let conditional_mul_pattern = format!("{mul_pattern}|{do_dont_pattern}");
-    let conditional_mul_pattern = Regex::new(&conditional_mul_pattern).unwrap();
-
-    let mut is_mul_enabled = true;
-
-    conditional_mul_pattern.captures_iter(input)
-        .map(|capture| {
-            let full_capture = capture.get(0).unwrap().as_str();
-            if full_capture == "do()" {
-                is_mul_enabled = true;
-                0
-            } else if full_capture == "don't()" {
-                is_mul_enabled = false;
-                0
-            } else if is_mul_enabled {
-                handle_mul_capture(capture)
-            } else {
-                0
+const DEFAULT_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Computes both sums in a single pass over the memory, reading `chunk_size` bytes at a time
+fn get_multiplication_sums(mut memory: impl Read, chunk_size: usize) -> io::Result<(u64, u64)> {
+    let mut scanner = StreamScanner::new(BASIC_INSTRUCTIONS);
+    let mut scanner_conditional = StreamScanner::new(CONDITIONAL_INSTRUCTIONS);
+    let mut chunk = vec![0; chunk_size.max(1)];
+    loop {
+        let length = match memory.read(&mut chunk) {
+            Ok(0) => break,
+            Ok(length) => length,
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+            Err(error) => return Err(error),
+        };
+        scanner.feed(&chunk[..length], &mut |_| {});
+        scanner_conditional.feed(&chunk[..length], &mut |_| {});
+    }
+    Ok((scanner.finish(), scanner_conditional.finish()))
+}
+
+/// The state of the computer while it runs the corrupted memory
+struct Machine {
+    is_mul_enabled: bool,
+    /// None once the sum, or one of the results added to it, has overflowed
+    sum: Option<u64>,
+}
+
+impl Default for Machine {
+    fn default() -> Self {
+        Machine {
+            is_mul_enabled: true,
+            sum: Some(0),
+        }
+    }
+}
+
+impl Machine {
+    fn add_to_sum(&mut self, result: Option<u64>) {
+        self.sum = self.sum.zip(result).and_then(|(sum, result)| sum.checked_add(result));
+    }
+}
+
+const OVERFLOW_MESSAGE: &str = "The sum overflowed a u64";
+
+/// How many arguments an instruction takes
+#[derive(Copy, Clone)]
+enum Arity {
+    Exactly(usize),
+    AtLeast(usize),
+}
+
+impl Arity {
+    fn allows(self, count: usize) -> bool {
+        match self {
+            Arity::Exactly(arity) => count == arity,
+            Arity::AtLeast(arity) => count >= arity,
+        }
+    }
+
+    /// Whether there can be more arguments than `count`
+    fn allows_more(self, count: usize) -> bool {
+        match self {
+            Arity::Exactly(arity) => count < arity,
+            Arity::AtLeast(_) => true,
+        }
+    }
+}
+
+struct Instruction {
+    name: &'static str,
+    arity: Arity,
+    /// Runs the instruction, returning false if it was skipped
+    execute: fn(&mut Machine, &[u64]) -> bool,
+}
+
+fn execute_mul(machine: &mut Machine, args: &[u64]) -> bool {
+    if machine.is_mul_enabled {
+        machine.add_to_sum(args.iter().try_fold(1u64, |product, &arg| product.checked_mul(arg)));
+    }
+    machine.is_mul_enabled
+}
+
+const MUL: Instruction = Instruction {
+    name: "mul",
+    arity: Arity::Exactly(2),
+    execute: execute_mul,
+};
+
+const DO: Instruction = Instruction {
+    name: "do",
+    arity: Arity::Exactly(0),
+    execute: |machine, _| {
+        machine.is_mul_enabled = true;
+        true
+    },
+};
+
+const DONT: Instruction = Instruction {
+    name: "don't",
+    arity: Arity::Exactly(0),
+    execute: |machine, _| {
+        machine.is_mul_enabled = false;
+        true
+    },
+};
+
+const BASIC_INSTRUCTIONS: &[Instruction] = &[MUL];
+const CONDITIONAL_INSTRUCTIONS: &[Instruction] = &[MUL, DO, DONT];
+/// `mul` with any number of arguments and an `add`, both of which are disabled by `don't()`
+const EXTENDED_INSTRUCTIONS: &[Instruction] = &[
+    Instruction {
+        name: "mul",
+        arity: Arity::AtLeast(1),
+        execute: execute_mul,
+    },
+    Instruction {
+        name: "add",
+        arity: Arity::AtLeast(1),
+        execute: |machine, args| {
+            if machine.is_mul_enabled {
+                machine.add_to_sum(args.iter().try_fold(0u64, |sum, &arg| sum.checked_add(arg)));
             }
-        })
-        .sum()
+            machine.is_mul_enabled
+        },
+    },
+    DO,
+    DONT,
+];
+
+/// Arguments are 1-3 digit numbers
+const MAX_ARGUMENT_DIGITS: usize = 3;
+
+/// An uncorrupted instruction found in the memory
+struct Call<'a> {
+    /// The byte offset of the start of the instruction's name
+    offset: usize,
+    instruction: &'a Instruction,
+    args: Vec<u64>,
+}
+
+impl Display for Call<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}({})", self.offset, self.instruction.name, self.args.iter().join(","))
+    }
+}
+
+/// The result of trying to read an instruction from one position in the memory
+enum Scan<'a> {
+    /// An instruction, and the number of bytes it took up
+    Call(Call<'a>, usize),
+    NoMatch,
+    /// The memory ran out part way through what could still be an instruction
+    Incomplete,
+}
+
+/// Tries to read an instruction starting exactly at `position`
+fn scan_at<'a>(memory: &[u8], position: usize, offset: usize, instructions: &'a [Instruction]) -> Scan<'a> {
+    let mut is_incomplete = false;
+    for instruction in instructions {
+        match scan_instruction_at(memory, position, instruction) {
+            Some(Some((args, length))) => return Scan::Call(Call { offset, instruction, args }, length),
+            Some(None) => is_incomplete = true,
+            None => {}
+        }
+    }
+    if is_incomplete {
+        Scan::Incomplete
+    } else {
+        Scan::NoMatch
+    }
+}
+
+/// Tries to read one particular instruction, returning None if it doesn't match and Some(None) if
+/// the memory ran out before it could tell
+fn scan_instruction_at(memory: &[u8], position: usize, instruction: &Instruction) -> Option<Option<(Vec<u64>, usize)>> {
+    let name = instruction.name.as_bytes();
+    let remaining = &memory[position..];
+    if remaining.len() <= name.len() {
+        // Can only tell if it's a match once the opening bracket is in the memory
+        return if name.starts_with(remaining) { Some(None) } else { None };
+    }
+    if !remaining.starts_with(name) || remaining[name.len()] != b'(' {
+        return None;
+    }
+
+    let mut args = vec![];
+    let mut i = name.len() + 1;
+    loop {
+        let digits = remaining[i..].iter().take_while(|byte| byte.is_ascii_digit()).count();
+        if digits > MAX_ARGUMENT_DIGITS {
+            return None;
+        }
+        if i + digits >= remaining.len() {
+            return Some(None);
+        }
+        if digits > 0 {
+            args.push(std::str::from_utf8(&remaining[i..(i + digits)]).unwrap().parse().unwrap());
+        }
+        i += digits;
+        match remaining[i] {
+            b')' if (digits > 0 || args.is_empty()) && instruction.arity.allows(args.len()) => {
+                return Some(Some((args, i + 1)));
+            },
+            // Give up on too many arguments straight away, rather than holding back the rest of
+            // the memory waiting for the closing bracket
+            b',' if digits > 0 && instruction.arity.allows_more(args.len()) => i += 1,
+            _ => return None,
+        }
+    }
+}
+
+/// Runs every instruction in the memory, calling `on_execute` for each one which wasn't skipped
+///
+/// Returns how many bytes at the end of the memory could still be the start of an instruction, if
+/// there is more memory to come. `offset` is the byte offset of the start of `memory`.
+fn run_memory(
+    memory: &[u8],
+    offset: usize,
+    instructions: &[Instruction],
+    machine: &mut Machine,
+    on_execute: &mut impl FnMut(&Call)
+) -> usize {
+    let mut position = 0;
+    while position < memory.len() {
+        match scan_at(memory, position, offset + position, instructions) {
+            Scan::Call(call, length) => {
+                if (call.instruction.execute)(machine, &call.args) {
+                    on_execute(&call);
+                }
+                position += length;
+            },
+            Scan::NoMatch => position += 1,
+            Scan::Incomplete => return memory.len() - position,
+        }
+    }
+    0
+}
+
+fn run_program(memory: &[u8], instructions: &[Instruction], machine: &mut Machine, mut on_execute: impl FnMut(&Call)) {
+    // Nothing more is coming so an incomplete instruction at the end is just corrupted
+    let _ = run_memory(memory, 0, instructions, machine, &mut on_execute);
+}
+
+fn get_program_sum(memory: &[u8], instructions: &[Instruction]) -> u64 {
+    let mut machine = Machine::default();
+    run_program(memory, instructions, &mut machine, |_| {});
+    machine.sum.expect(OVERFLOW_MESSAGE)
 }
 
 #[test]
@@ -132,3 +399,63 @@ fn test_part2() {
                )
     );
 }
+
+#[test]
+fn test_multiplication_sums() {
+    // The don't() on the first line still applies to the second, and the do() on the second to the third
+    let memory = r"xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64]
+(mul(11,8)undo()?mul(8,5))
+xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))";
+    // Every chunk size splits some instructions in a different place
+    for chunk_size in 1..=memory.len() {
+        assert_eq!((161 + 161, 48 + 161), get_multiplication_sums(memory.as_bytes(), chunk_size).unwrap());
+    }
+
+    // An instruction with too many arguments which never ends doesn't hold back the whole memory
+    let mut scanner = StreamScanner::new(CONDITIONAL_INSTRUCTIONS);
+    scanner.feed(b"mul(1", &mut |_| {});
+    for _ in 0..1000 {
+        scanner.feed(b",1", &mut |_| {});
+        assert!(scanner.pending.len() < 8);
+    }
+    scanner.feed(b")mul(2,3)", &mut |_| {});
+    assert_eq!(6, scanner.finish());
+}
+
+#[test]
+fn test_program() {
+    let part1 = b"xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))";
+    let part2 = b"xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+    assert_eq!(161, get_program_sum(part1, BASIC_INSTRUCTIONS));
+    assert_eq!(161, get_program_sum(part2, BASIC_INSTRUCTIONS));
+    assert_eq!(48, get_program_sum(part2, CONDITIONAL_INSTRUCTIONS));
+
+    let mut executed = vec![];
+    run_program(part2, CONDITIONAL_INSTRUCTIONS, &mut Machine::default(), |call| executed.push(call.to_string()));
+    assert_eq!(vec!["1: mul(2,4)", "20: don't()", "59: do()", "64: mul(8,5)"], executed);
+
+    // The trailing mul( could have been an instruction if there was more memory
+    assert_eq!(0, run_memory(b"mul(1,2)", 0, BASIC_INSTRUCTIONS, &mut Machine::default(), &mut |_| {}));
+    assert_eq!(7, run_memory(b"mul(1,2)xmul(12,", 0, BASIC_INSTRUCTIONS, &mut Machine::default(), &mut |_| {}));
+}
+
+#[test]
+fn test_extended_program() {
+    assert_eq!(2 * 3 * 4 + 5 + 6 + 7 + 8,
+               get_program_sum(b"mul(2,3,4)add(5,6)mul(7)don't()add(1,1)do()add(8)mul()add(1,,2)", EXTENDED_INSTRUCTIONS)
+    );
+}
+
+#[test]
+fn test_overflow() {
+    let run = |memory: &[u8]| {
+        let mut machine = Machine::default();
+        run_program(memory, EXTENDED_INSTRUCTIONS, &mut machine, |_| {});
+        machine.sum
+    };
+    assert_eq!(Some(994014980014994001), run(b"mul(999,999,999,999,999,999)"));
+    // 999^7 is too big for a u64, as are nineteen lots of 999^6
+    assert_eq!(None, run(b"mul(999,999,999,999,999,999,999)"));
+    assert_eq!(None, run(&b"mul(999,999,999,999,999,999)".repeat(19)));
+    assert_eq!(None, run(b"mul(999,999,999,999,999,999,999)add(1)"));
+}
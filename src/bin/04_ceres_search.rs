@@ -88,55 +88,258 @@ Flip the word search from the instructions back over to the word search
 side and try again. How many times does an X-MAS appear?
  */
 
-use std::fs;
+use advent_of_code2024::runner::Runner;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 use itertools::Itertools;
 
 fn main() {
-    let input_file = fs::read_to_string("./inputs/04_ceres_search.txt").unwrap();
+    let runner = Runner::from_args("04_ceres_search").solver_version("2");
+    let input_file = runner.read_input();
     let input_lines = input_file.lines().collect_vec();
-    println!("{}", get_xmas_count(input_lines.clone()));
-    println!("{}", get_x_mas_count(input_lines));
+    if let Some(template) = runner.option("--template") {
+        let symmetries = runner.parsed_option("--symmetries").unwrap_or(Symmetries::RotationsAndReflections);
+        let search = WordSearch { input: input_lines };
+        let matches = search.find_matches(&Template::parse(template), symmetries);
+        for template_match in &matches {
+            println!("{},{} {}", template_match.x, template_match.y, template_match.orientation);
+        }
+        println!("{}", matches.len());
+        return;
+    }
+    if let Some(words) = runner.option("--words") {
+        let search = WordSearch { input: input_lines };
+        let trie = Trie::new(words.split(','));
+        let matches = search.find_words(&trie);
+        for word_match in &matches {
+            println!(
+                "{} {},{} {}",
+                trie.words[word_match.word], word_match.x, word_match.y, direction_name(word_match.direction)
+            );
+        }
+        println!("{}", matches.len());
+        if runner.has_flag("--render") {
+            print!("{}", search.render_words(&trie, &matches));
+        }
+        return;
+    }
+    runner.run_part(1, &input_file, || get_xmas_count(input_lines.clone()));
+    runner.run_part(2, &input_file, || get_x_mas_count(input_lines));
+}
+
+/// "XMAS" written horizontally and diagonally, the rotations give the other six directions
+const XMAS: &str = "XMAS";
+const XMAS_DIAGONAL: &str = "X.../.M../..A./...S";
+/// Two "MAS" in the shape of an X
+const X_MAS: &str = "M.S/.A./M.S";
+
+/// Which orientations of a template to look for as well as the one it was written in
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Symmetries {
+    None,
+    /// Quarter turns
+    Rotations,
+    /// Quarter turns, each optionally mirrored left to right first
+    RotationsAndReflections,
+}
+
+impl FromStr for Symmetries {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(Symmetries::None),
+            "rotations" => Ok(Symmetries::Rotations),
+            "all" => Ok(Symmetries::RotationsAndReflections),
+            _ => Err(format!("Unknown symmetries: {s}")),
+        }
+    }
+}
+
+/// How a template was turned to make a match
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+struct Orientation {
+    is_reflected: bool,
+    quarter_turns: u8,
+}
+
+impl Orientation {
+    fn all(symmetries: Symmetries) -> Vec<Orientation> {
+        let (reflections, quarter_turns) = match symmetries {
+            Symmetries::None => (vec![false], 0..1),
+            Symmetries::Rotations => (vec![false], 0..4),
+            Symmetries::RotationsAndReflections => (vec![false, true], 0..4),
+        };
+        reflections.into_iter()
+            .cartesian_product(quarter_turns)
+            .map(|(is_reflected, quarter_turns)| Orientation { is_reflected, quarter_turns })
+            .collect()
+    }
+
+    /// Moves an offset from the template's top left cell, the reflection happens before the turns
+    fn apply(self, (mut x, mut y): (i64, i64)) -> (i64, i64) {
+        if self.is_reflected {
+            x = -x;
+        }
+        for _ in 0..self.quarter_turns {
+            (x, y) = (-y, x);
+        }
+        (x, y)
+    }
+}
+
+impl Display for Orientation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.is_reflected {
+            write!(f, "reflected, ")?;
+        }
+        write!(f, "rotated {}°", self.quarter_turns as u32 * 90)
+    }
+}
+
+/// An offset from the top left cell and the letter to match there, None for `?`
+type TemplateCell = (i64, i64, Option<char>);
+
+/// A small 2D pattern of letters, written as rows separated by `/`
+///
+/// `?` matches any letter and `.` matches anything at all, including the edge of the grid.
+struct Template {
+    cells: Vec<TemplateCell>,
 }
 
+impl Template {
+    fn parse(pattern: &str) -> Template {
+        let cells = pattern.split('/')
+            .enumerate()
+            .flat_map(|(y, row)| row.chars()
+                .enumerate()
+                .filter(|&(_, c)| c != '.')
+                .map(move |(x, c)| (x as i64, y as i64, if c == '?' { None } else { Some(c) }))
+            )
+            .collect();
+        Template { cells }
+    }
+
+    /// Each distinct way the template can appear, symmetric templates (e.g. a palindrome) would
+    /// otherwise be matched more than once in the same place
+    fn orientations(&self, symmetries: Symmetries) -> Vec<(Orientation, Vec<TemplateCell>)> {
+        let mut orientations: Vec<(Orientation, Vec<TemplateCell>)> = vec![];
+        for orientation in Orientation::all(symmetries) {
+            let cells = self.cells.iter()
+                .map(|&(x, y, c)| {
+                    let (x, y) = orientation.apply((x, y));
+                    (x, y, c)
+                })
+                .sorted()
+                .collect_vec();
+            if orientations.iter().all(|(_, other_cells)| !is_same_shape(other_cells, &cells)) {
+                orientations.push((orientation, cells));
+            }
+        }
+        orientations
+    }
+}
+
+/// Whether two sorted lists of cells are the same apart from where they are anchored
+fn is_same_shape(cells: &[TemplateCell], other_cells: &[TemplateCell]) -> bool {
+    let (Some(&(x, y, _)), Some(&(other_x, other_y, _))) = (cells.first(), other_cells.first()) else {
+        return cells.len() == other_cells.len();
+    };
+    cells.len() == other_cells.len() && cells.iter()
+        .zip(other_cells)
+        .all(|(&(cell_x, cell_y, c), &(other_cell_x, other_cell_y, other_c))| {
+            (cell_x - x, cell_y - y, c) == (other_cell_x - other_x, other_cell_y - other_y, other_c)
+        })
+}
+
+/// Every direction a word can be written in, with the same order as the points of a compass
 const DIRECTIONS: [(i64, i64); 8] = [
-    (1, 0),
-    (-1, 0),
-    (0, 1),
     (0, -1),
-    (1, 1),
     (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
     (-1, 1),
+    (-1, 0),
     (-1, -1),
 ];
-const XMAS: [char; 4] = ['X', 'M', 'A', 'S'];
-
-// The four rotation matrices we will use
-const ROTATIONS: [((i64, i64), (i64, i64)); 4] = [
-    (
-        (1, 0),
-        (0, 1),
-    ),
-    (
-      (0, -1),
-      (1, 0),
-    ),
-    (
-      (-1, 0),
-      (0, -1),
-    ),
-    (
-      (0, 1),
-      (-1, 0),
-    ),
-];
-// An "image" of the pattern
-const X_MAS: [(i64, i64, char); 5] = [
-    (0, 0, 'A'),
-    (-1, -1, 'M'),
-    (-1, 1, 'M'),
-    (1, -1, 'S'),
-    (1, 1, 'S'),
-];
+
+fn direction_name(direction: (i64, i64)) -> &'static str {
+    match direction {
+        (0, -1) => "up",
+        (1, -1) => "up-right",
+        (1, 0) => "right",
+        (1, 1) => "down-right",
+        (0, 1) => "down",
+        (-1, 1) => "down-left",
+        (-1, 0) => "left",
+        (-1, -1) => "up-left",
+        _ => panic!("Not a direction: {direction:?}"),
+    }
+}
+
+/// A dictionary of words arranged so that all of them can be followed a letter at a time at once
+struct Trie<'a> {
+    words: Vec<&'a str>,
+    /// The root is node 0
+    nodes: Vec<TrieNode>,
+}
+
+#[derive(Default)]
+struct TrieNode {
+    children: HashMap<char, usize>,
+    /// The index of the word which ends at this node, if any
+    word: Option<usize>,
+}
+
+impl<'a> Trie<'a> {
+    fn new(words: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut trie = Trie {
+            words: vec![],
+            nodes: vec![TrieNode::default()],
+        };
+        for word in words {
+            let mut node = 0;
+            for c in word.chars() {
+                node = match trie.nodes[node].children.get(&c) {
+                    Some(&child) => child,
+                    None => {
+                        trie.nodes.push(TrieNode::default());
+                        let child = trie.nodes.len() - 1;
+                        trie.nodes[node].children.insert(c, child);
+                        child
+                    }
+                };
+            }
+            trie.nodes[node].word = Some(trie.words.len());
+            trie.words.push(word);
+        }
+        trie
+    }
+
+    fn child(&self, node: usize, c: char) -> Option<usize> {
+        self.nodes[node].children.get(&c).copied()
+    }
+}
+
+/// A dictionary word found in the grid, starting at (x, y)
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+struct WordMatch {
+    /// The index of the word in the trie
+    word: usize,
+    x: i64,
+    y: i64,
+    direction: (i64, i64),
+}
+
+/// Where a template was found, (x, y) is the position of its top left cell before it was turned
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+struct TemplateMatch {
+    x: i64,
+    y: i64,
+    orientation: Orientation,
+}
 
 struct WordSearch<'a> {
     input: Vec<&'a str>
@@ -151,8 +354,12 @@ impl<'a> WordSearch<'a> {
         self.input.len() as i64
     }
 
+    fn is_on_grid(&self, x: i64, y: i64) -> bool {
+        x >= 0 && x < self.width() && y >= 0 && y < self.height()
+    }
+
     fn get(&self, x: i64, y: i64) -> char {
-        if x < 0 || x >= self.width() || y < 0 || y >= self.height() {
+        if !self.is_on_grid(x, y) {
             return '.';
         }
 
@@ -161,61 +368,97 @@ impl<'a> WordSearch<'a> {
     }
 }
 
-fn get_xmas_count(input_lines: Vec<&str>) -> u64 {
-    // This is basically just how I solve word searches when doing them manually
-    let mut count = 0u64;
-
-    let search = WordSearch {
-        input: input_lines
-    };
+impl WordSearch<'_> {
+    fn find_matches(&self, template: &Template, symmetries: Symmetries) -> Vec<TemplateMatch> {
+        let orientations = template.orientations(symmetries);
+        let mut matches = vec![];
+        for y in 0..self.height() {
+            for x in 0..self.width() {
+                for (orientation, cells) in &orientations {
+                    let is_match = cells.iter().all(|&(offset_x, offset_y, expected)| {
+                        let c = self.get(x + offset_x, y + offset_y);
+                        match expected {
+                            Some(expected) => c == expected,
+                            // Off the grid is '.', which isn't a letter
+                            None => c != '.',
+                        }
+                    });
+                    if is_match {
+                        matches.push(TemplateMatch { x, y, orientation: *orientation });
+                    }
+                }
+            }
+        }
+        matches
+    }
+}
 
-    for x in 0..search.width() {
-        for y in 0..search.height() {
-            'direction_loop: for d in DIRECTIONS {
-                for i in 0..XMAS.len() {
-                    let x = x + (i as i64) * d.0;
-                    let y = y + (i as i64) * d.1;
-                    let c = search.get(x, y);
-                    if c != XMAS[i] {
-                        continue 'direction_loop;
+impl WordSearch<'_> {
+    /// Finds every word from the dictionary, walking the trie from each cell in each direction so
+    /// that words sharing a prefix are only read once
+    fn find_words(&self, trie: &Trie) -> Vec<WordMatch> {
+        let mut matches = vec![];
+        for y in 0..self.height() {
+            for x in 0..self.width() {
+                for direction in DIRECTIONS {
+                    let mut node = 0;
+                    let (mut cell_x, mut cell_y) = (x, y);
+                    // Off the grid never matches, even a word with a '.' in it
+                    while self.is_on_grid(cell_x, cell_y) {
+                        let Some(child) = trie.child(node, self.get(cell_x, cell_y)) else {
+                            break;
+                        };
+                        node = child;
+                        if let Some(word) = trie.nodes[node].word {
+                            matches.push(WordMatch { word, x, y, direction });
+                        }
+                        cell_x += direction.0;
+                        cell_y += direction.1;
                     }
                 }
-                // Increment count if we found the whole word
-                count += 1;
             }
         }
+        matches
     }
 
-    count
-}
+    /// The grid with every cell that isn't part of a found word blanked out with `.`
+    fn render_words(&self, trie: &Trie, matches: &[WordMatch]) -> String {
+        let mut is_found = vec![vec![false; self.width() as usize]; self.height() as usize];
+        for word_match in matches {
+            for i in 0..(trie.words[word_match.word].chars().count() as i64) {
+                let x = word_match.x + i * word_match.direction.0;
+                let y = word_match.y + i * word_match.direction.1;
+                is_found[y as usize][x as usize] = true;
+            }
+        }
 
-fn get_x_mas_count(input_lines: Vec<&str>) -> u64 {
-    // This is like the previous solution but a more complex pattern
-    // We're basically just going to try to apply the pattern everywhere we can
-    let mut count = 0u64;
+        let mut result = String::new();
+        for y in 0..self.height() {
+            for x in 0..self.width() {
+                result.push(if is_found[y as usize][x as usize] { self.get(x, y) } else { '.' });
+            }
+            result.push('\n');
+        }
+        result
+    }
+}
 
+fn get_template_count(input_lines: Vec<&str>, templates: &[&str], symmetries: Symmetries) -> u64 {
     let search = WordSearch {
         input: input_lines
     };
 
-    for x in 0..search.width() {
-        for y in 0..search.height() {
-            'rotation_loop: for ((x_x, x_y), (y_x, y_y)) in ROTATIONS {
-                for (offset_x, offset_y, expected) in X_MAS {
-                    let x = x + x_x * offset_x + x_y * offset_y;
-                    let y = y + y_x * offset_x + y_y * offset_y;
-                    let c = search.get(x, y);
-                    if c != expected {
-                        continue 'rotation_loop;
-                    }
-                }
-                // Increment count if we found the whole word
-                count += 1;
-            }
-        }
-    }
+    templates.iter()
+        .map(|template| search.find_matches(&Template::parse(template), symmetries).len() as u64)
+        .sum()
+}
 
-    count
+fn get_xmas_count(input_lines: Vec<&str>) -> u64 {
+    get_template_count(input_lines, &[XMAS, XMAS_DIAGONAL], Symmetries::Rotations)
+}
+
+fn get_x_mas_count(input_lines: Vec<&str>) -> u64 {
+    get_template_count(input_lines, &[X_MAS], Symmetries::Rotations)
 }
 
 #[test]
@@ -253,3 +496,86 @@ MXMXAXMASX".lines().collect_vec()
                )
     );
 }
+
+#[test]
+fn test_find_matches() {
+    let search = WordSearch {
+        input: vec![
+            "ABC.",
+            "..AB",
+            "..CA",
+        ]
+    };
+    // An L shape, the A at (2, 1) is the corner of two different ones
+    let matches = search.find_matches(&Template::parse("A?/B."), Symmetries::RotationsAndReflections);
+    assert_eq!(vec![
+        TemplateMatch { x: 2, y: 1, orientation: Orientation { is_reflected: false, quarter_turns: 3 } },
+        TemplateMatch { x: 2, y: 1, orientation: Orientation { is_reflected: true, quarter_turns: 3 } },
+        TemplateMatch { x: 3, y: 2, orientation: Orientation { is_reflected: false, quarter_turns: 2 } },
+    ], matches);
+    assert!(search.find_matches(&Template::parse("A?/B."), Symmetries::None).is_empty());
+
+    // The X is symmetric, so the reflections don't find the same matches again
+    let input = "MMMSXXMASM
+MSAMXMSMSA
+AMXSXMAAMM
+MSAMASMSMX
+XMASAMXAMM
+XXAMMXXAMA
+SMSMSASXSS
+SAXAMASAAA
+MAMMMXMMMM
+MXMXAXMASX";
+    assert_eq!(9, get_template_count(input.lines().collect_vec(), &[X_MAS], Symmetries::RotationsAndReflections));
+}
+
+#[test]
+fn test_find_words() {
+    let search = WordSearch {
+        input: vec![
+            "XMAS.",
+            ".A..S",
+            ".X...",
+        ]
+    };
+    let trie = Trie::new(["XMAS", "XMA", "AX"]);
+    assert_eq!(vec![
+        WordMatch { word: 1, x: 0, y: 0, direction: (1, 0) },
+        WordMatch { word: 0, x: 0, y: 0, direction: (1, 0) },
+        WordMatch { word: 2, x: 1, y: 1, direction: (0, 1) },
+        WordMatch { word: 2, x: 1, y: 1, direction: (-1, -1) },
+    ], search.find_words(&trie));
+    assert_eq!("XMAS.
+.A...
+.X...
+", search.render_words(&trie, &search.find_words(&trie)));
+
+    // Words can include '.', but only the grid's own, not the '.' off the edge of it
+    let search = WordSearch {
+        input: vec![
+            "AB",
+            "C.",
+        ]
+    };
+    let trie = Trie::new(["B.", "C."]);
+    assert_eq!(vec![
+        WordMatch { word: 0, x: 1, y: 0, direction: (0, 1) },
+        WordMatch { word: 1, x: 0, y: 1, direction: (1, 0) },
+    ], search.find_words(&trie));
+    assert_eq!(".B
+C.
+", search.render_words(&trie, &search.find_words(&trie)));
+
+    // The trie finds the same XMASes as the template search
+    let input = "MMMSXXMASM
+MSAMXMSMSA
+AMXSXMAAMM
+MSAMASMSMX
+XMASAMXAMM
+XXAMMXXAMA
+SMSMSASXSS
+SAXAMASAAA
+MAMMMXMMMM
+MXMXAXMASX";
+    assert_eq!(18, WordSearch { input: input.lines().collect_vec() }.find_words(&Trie::new(["XMAS"])).len());
+}
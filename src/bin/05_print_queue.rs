@@ -135,52 +135,58 @@ together produces 123.
 Find the updates which are not in the correct order. What do you get if you
 add up the middle page numbers after correctly ordering just those updates?
  */
-use advent_of_code2024::dfs::depth_first_search;
+use advent_of_code2024::precedence::PrecedenceRules;
 use itertools::Itertools;
-use std::collections::{HashMap, HashSet};
-use std::fs;
+use advent_of_code2024::runner::Runner;
 
 fn main() {
-    let input_file = fs::read_to_string("./inputs/05_print_queue.txt").unwrap();
-    println!("{}", get_sum_correct_middle_page_numbers(&input_file));
-    println!("{}", get_sum_incorrect_middle_page_numbers(&input_file));
+    let runner = Runner::from_args("05_print_queue").solver_version("2");
+    let input_file = runner.read_input();
+    if runner.has_flag("--analyse") {
+        let mut lines = input_file.lines();
+        let rules = PrecedenceRules::parse_lines(&mut lines).unwrap();
+        for (update_index, line) in lines.enumerate() {
+            let pages = parse_update(line);
+            let analysis = analyse_update(&rules, &pages);
+            if !analysis.violations.is_empty() {
+                println!("Update {} ({line}):\n{}", update_index + 1, describe_analysis(&analysis));
+            }
+        }
+        return;
+    }
+    runner.run_part(1, &input_file, || get_sum_correct_middle_page_numbers(&input_file));
+    runner.run_part(2, &input_file, || get_sum_incorrect_middle_page_numbers(&input_file));
+}
+
+fn parse_update(line: &str) -> Vec<u64> {
+    line.split(',').map(|page| page.parse::<u64>().unwrap()).collect_vec()
 }
 
-/// A helper that parses the page ordering rules into a 1:many mapping of before -> after
-/// This then runs through all the page updates and determines if they are correct or incorrect
+/// A helper that parses the page ordering rules and then runs through all the page updates to
+/// determine if they are correct or incorrect
 /// It then applies mapping functions to determine the value of the correct and incorrect mappings
 /// And finally sums up the values of all updates
 fn eval_on_pages(
     input: &str,
-    on_incorrect: impl Fn(&HashMap<u64, HashSet<u64>>, Vec<u64>)->u64,
-    on_correct: impl Fn(&HashMap<u64, HashSet<u64>>, Vec<u64>)->u64
+    on_incorrect: impl Fn(&PrecedenceRules, Vec<u64>)->u64,
+    on_correct: impl Fn(&PrecedenceRules, Vec<u64>)->u64
 ) -> u64 {
-    let mut before_mapping = HashMap::<u64, HashSet<u64>>::new();
     let mut lines = input.lines();
-    while let Some(line) = lines.next() {
-        let Some((left, right)) = line.split_once('|') else { break; };
-        let before = left.parse::<u64>().unwrap();
-        let after = right.parse::<u64>().unwrap();
-        if !before_mapping.contains_key(&before) {
-            before_mapping.insert(before, HashSet::new());
-        }
-        before_mapping.get_mut(&before).unwrap().insert(after);
-    }
+    let rules = PrecedenceRules::parse_lines(&mut lines).unwrap();
 
     lines.map(|line| {
-        let pages = line.split(',').map(|page| page.parse::<u64>().unwrap()).collect_vec();
-        let mut previous_pages = HashSet::new();
-        for page in &pages {
-            // If there is nothing that needs to appear after this then no problem
-            if let Some(after_set) = before_mapping.get(&page) {
-                // If one of the previous pages needs to be after this page then this isn't valid
-                if !after_set.is_disjoint(&previous_pages) {
-                    return on_incorrect(&before_mapping, pages);
-                }
-            }
-            previous_pages.insert(*page);
+        let pages = parse_update(line);
+        // Only check every pair of pages when the rules don't order them all
+        let is_ordered = if rules.is_total_over(&pages) {
+            rules.is_ordered(&pages)
+        } else {
+            rules.is_ordered_checked(&pages)
+        };
+        if is_ordered {
+            on_correct(&rules, pages)
+        } else {
+            on_incorrect(&rules, pages)
         }
-        on_correct(&before_mapping, pages)
     }).sum()
 }
 
@@ -191,21 +197,126 @@ fn get_sum_correct_middle_page_numbers(input: &str) -> u64 {
 
 /// Solve part 2
 fn get_sum_incorrect_middle_page_numbers(input: &str) -> u64 {
-    let empty = HashSet::default();
-    eval_on_pages(input, |before_mapping, pages| {
-        // Using DFS to perform a topological sorting
-        let mut sorted_pages = Vec::with_capacity(pages.len());
-
-        let _ = depth_first_search(
-            &pages,
-            |page| before_mapping.get(page).unwrap_or(&empty).iter(),
-            |page| sorted_pages.insert(0, *page)
-        ).expect("DFS Failed");
-
-        sorted_pages[sorted_pages.len() / 2]
+    eval_on_pages(input, |rules, mut pages| {
+        // The rules can only be used as a comparator when they're a total order over the pages
+        if rules.is_total_over(&pages) {
+            rules.sort(&mut pages);
+        } else {
+            rules.checked_sort(&mut pages).expect("The rules between an update's pages form a cycle");
+        }
+        pages[pages.len() / 2]
     }, |_, _| 0)
 }
 
+/// A `before|after` rule broken by an update, because `after` is printed first
+#[derive(Debug, Eq, PartialEq)]
+struct Violation {
+    before_index: usize,
+    before_page: u64,
+    after_index: usize,
+    after_page: u64,
+}
+
+/// Why an update is in the wrong order and how much work it is to fix
+#[derive(Debug, Eq, PartialEq)]
+struct UpdateAnalysis {
+    violations: Vec<Violation>,
+    /// Whether the rules between the update's pages have no cycles, i.e. whether any order is correct
+    is_acyclic: bool,
+    /// The fewest pages which need to be moved to put the update in a correct order, None if there
+    /// isn't one
+    min_pages_to_move: Option<usize>,
+}
+
+fn analyse_update(rules: &PrecedenceRules, pages: &[u64]) -> UpdateAnalysis {
+    let must_precede = |before: u64, after: u64| rules.must_precede(before, after);
+
+    let violations = pages.iter()
+        .enumerate()
+        .tuple_combinations()
+        .filter(|&((_, &after_page), (_, &before_page))| must_precede(before_page, after_page))
+        .map(|((after_index, &after_page), (before_index, &before_page))| Violation {
+            before_index,
+            before_page,
+            after_index,
+            after_page,
+        })
+        .collect_vec();
+
+    let is_acyclic = rules.checked_sort(&mut pages.to_vec()).is_ok();
+
+    UpdateAnalysis {
+        violations,
+        is_acyclic,
+        min_pages_to_move: is_acyclic.then(|| get_min_pages_to_move(pages, must_precede)),
+    }
+}
+
+/// The pages left where they are must not break any rules between themselves, and with no cycles
+/// the moved pages can always be slotted in around them. Two pages in the wrong order (following
+/// rules transitively) can't both stay, and this is a partial order, so the most pages that can
+/// stay is its largest antichain. By Dilworth's theorem that is the number of pages minus the size
+/// of a maximum matching between each page and the pages it's out of order with, so the matching
+/// size is the number of pages to move.
+fn get_min_pages_to_move(pages: &[u64], must_precede: impl Fn(u64, u64) -> bool) -> usize {
+    let n = pages.len();
+    // Transitive closure of the rules between the update's pages
+    let mut precedes = (0..n)
+        .map(|i| (0..n).map(|j| must_precede(pages[i], pages[j])).collect_vec())
+        .collect_vec();
+    for k in 0..n {
+        for i in 0..n {
+            for j in 0..n {
+                precedes[i][j] |= precedes[i][k] && precedes[k][j];
+            }
+        }
+    }
+    let out_of_order = |i: usize, j: usize| i < j && precedes[j][i];
+
+    // Kuhn's augmenting path algorithm
+    fn try_match(
+        i: usize,
+        n: usize,
+        out_of_order: &impl Fn(usize, usize) -> bool,
+        is_tried: &mut [bool],
+        matched_to: &mut [Option<usize>],
+    ) -> bool {
+        for j in 0..n {
+            if out_of_order(i, j) && !is_tried[j] {
+                is_tried[j] = true;
+                if matched_to[j].is_none_or(|other| try_match(other, n, out_of_order, is_tried, matched_to)) {
+                    matched_to[j] = Some(i);
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    let mut matched_to = vec![None; n];
+    (0..n).filter(|&i| try_match(i, n, &out_of_order, &mut vec![false; n], &mut matched_to)).count()
+}
+
+fn describe_analysis(analysis: &UpdateAnalysis) -> String {
+    let mut lines = analysis.violations.iter()
+        .map(|violation| format!(
+            "  {}|{} is broken: {} (index {}) is printed before {} (index {})",
+            violation.before_page,
+            violation.after_page,
+            violation.after_page,
+            violation.after_index,
+            violation.before_page,
+            violation.before_index
+        ))
+        .collect_vec();
+    lines.push(match analysis.min_pages_to_move {
+        Some(1) => "  Fixed by moving 1 page".to_string(),
+        Some(pages) => format!("  Fixed by moving {pages} pages"),
+        None => "  Can't be fixed, the rules between its pages form a cycle".to_string(),
+    });
+    lines.join("\n")
+}
+
 #[test]
 fn test_part1() {
     assert_eq!(143,
@@ -277,3 +388,65 @@ fn test_part2() {
                )
     );
 }
+
+#[test]
+fn test_analyse_update() {
+    let input = "47|53
+97|13
+97|61
+97|47
+75|29
+61|13
+75|53
+29|13
+97|29
+53|29
+61|53
+97|53
+61|29
+47|13
+75|47
+97|75
+47|61
+75|61
+47|29
+75|13
+53|13";
+    let rules: PrecedenceRules = input.parse().unwrap();
+
+    assert_eq!(
+        UpdateAnalysis { violations: vec![], is_acyclic: true, min_pages_to_move: Some(0) },
+        analyse_update(&rules, &[75, 47, 61, 53, 29])
+    );
+    assert_eq!(
+        UpdateAnalysis {
+            violations: vec![Violation { before_index: 1, before_page: 97, after_index: 0, after_page: 75 }],
+            is_acyclic: true,
+            min_pages_to_move: Some(1),
+        },
+        analyse_update(&rules, &[75, 97, 47, 61, 53])
+    );
+
+    // 97,75,47,29,13 is the correct order, keeping 97, 75 and 47 in place means moving the other two
+    let analysis = analyse_update(&rules, &[97, 13, 75, 29, 47]);
+    assert_eq!(4, analysis.violations.len());
+    assert_eq!(Some(2), analysis.min_pages_to_move);
+
+    let cyclic_rules: PrecedenceRules = "1|2\n2|3\n3|1".parse().unwrap();
+    let analysis = analyse_update(&cyclic_rules, &[1, 2, 3]);
+    assert_eq!(vec![Violation { before_index: 2, before_page: 3, after_index: 0, after_page: 1 }], analysis.violations);
+    assert!(!analysis.is_acyclic);
+    assert_eq!(None, analysis.min_pages_to_move);
+}
+
+#[test]
+fn test_rules_not_total_order() {
+    // 2 isn't ordered against the other pages, so only checking neighbours would miss that 3 is
+    // printed before 1
+    let input = r"1|3
+
+3,2,1
+1,2,3";
+    assert_eq!(2, get_sum_correct_middle_page_numbers(input));
+    assert_eq!(1, get_sum_incorrect_middle_page_numbers(input));
+}
@@ -235,22 +235,83 @@ obstruction. How many different positions could you choose for this
 obstruction?
  */
 
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
 use itertools::Itertools;
-use std::fs;
+use advent_of_code2024::runner::Runner;
+use advent_of_code2024::visualise::{Colour, Grid, Legend, Renderer};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
 
 fn main() {
-    let input_file = fs::read_to_string("./inputs/06_guard_gallivant.txt").unwrap();
-    println!("{}", get_distinct_guard_positions(&input_file));
-    println!("{}", get_potential_obstructions(&input_file));
+    let runner = Runner::from_args("06_guard_gallivant").solver_version("2");
+    let input_file = runner.read_input();
+    if runner.has_flag("--visualise") {
+        visualise_guard_walk(&input_file, &mut runner.renderer(DisplayTile::legend()));
+        return;
+    }
+    if runner.has_flag("--route") {
+        let routes = get_guard_routes(&input_file);
+        for (guard, route) in routes.iter().enumerate() {
+            if routes.len() > 1 {
+                println!("Guard {guard}:");
+            }
+            for segment in route {
+                println!("{segment}");
+            }
+        }
+        return;
+    }
+    if runner.has_flag("--loops") {
+        for witness in get_loop_witnesses(&input_file) {
+            println!(
+                "Obstruction at {},{} loops guard {} from {},{} {:?}:",
+                witness.obstruction.0,
+                witness.obstruction.1,
+                witness.guard,
+                witness.entry.x,
+                witness.entry.y,
+                witness.entry.direction
+            );
+            for segment in witness.cycle {
+                println!("  {segment}");
+            }
+        }
+        return;
+    }
+    runner.run_part(1, &input_file, || get_distinct_guard_positions(&input_file));
+    runner.run_part(2, &input_file, || get_potential_obstructions(&input_file));
 }
 
 #[derive(Copy, Clone, Eq, PartialEq)]
 enum Tile {
     Free,
+    /// The guard turns right in front of it
     Obstacle,
+    /// The guard turns left in front of it
+    Deflector,
+    /// Can only be walked onto going in its direction, otherwise it's an obstacle
+    OneWay(Direction),
+    /// Walking onto it puts the guard on the other teleport with the same id instead
+    Teleport(u8),
 }
 
-#[derive(Copy, Clone, Eq, PartialEq)]
+impl Tile {
+    fn from_symbol(c: char) -> Option<Tile> {
+        match c {
+            '.' => Some(Tile::Free),
+            '#' => Some(Tile::Obstacle),
+            '*' => Some(Tile::Deflector),
+            'n' => Some(Tile::OneWay(Direction::Up)),
+            'e' => Some(Tile::OneWay(Direction::Right)),
+            's' => Some(Tile::OneWay(Direction::Down)),
+            'w' => Some(Tile::OneWay(Direction::Left)),
+            '0'..='9' => Some(Tile::Teleport(c as u8 - b'0')),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
 enum Direction {
     Up,
     Right,
@@ -259,6 +320,16 @@ enum Direction {
 }
 
 impl Direction {
+    fn from_guard_symbol(c: char) -> Option<Direction> {
+        match c {
+            '^' => Some(Direction::Up),
+            '>' => Some(Direction::Right),
+            'v' => Some(Direction::Down),
+            '<' => Some(Direction::Left),
+            _ => None,
+        }
+    }
+
     fn turn_right(self) -> Self {
         match self {
             Direction::Up => Direction::Right,
@@ -268,6 +339,10 @@ impl Direction {
         }
     }
 
+    fn turn_left(self) -> Self {
+        self.turn_right().turn_right().turn_right()
+    }
+
     fn unit_vector(self) -> (i64, i64) {
         match self {
             Direction::Up => (0, -1),
@@ -287,7 +362,7 @@ impl Direction {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
 struct GuardState {
     x: i64,
     y: i64,
@@ -311,10 +386,16 @@ impl GuardState {
     fn turn_right(&mut self) {
         self.direction = self.direction.turn_right();
     }
+
+    fn turn_left(&mut self) {
+        self.direction = self.direction.turn_left();
+    }
 }
 
 struct Map {
     tiles: Vec<Tile>,
+    /// Each teleport's position mapped to the position of its partner
+    teleports: HashMap<(i64, i64), (i64, i64)>,
     visited: Vec<bool>,
     width: usize,
     height: usize,
@@ -344,6 +425,20 @@ impl Map {
         }
     }
 
+    /// Moves the guard one step or turns them, depending on what's in front of them
+    fn move_guard(&self, guard: &mut GuardState, obstruction: Option<(i64, i64)>) {
+        let (x, y) = guard.looking_at();
+        let tile = if obstruction == Some((x, y)) { Tile::Obstacle } else { self.get_tile(x, y) };
+        match tile {
+            Tile::Free => guard.step(),
+            Tile::Obstacle => guard.turn_right(),
+            Tile::Deflector => guard.turn_left(),
+            Tile::OneWay(direction) if direction == guard.direction => guard.step(),
+            Tile::OneWay(_) => guard.turn_right(),
+            Tile::Teleport(_) => (guard.x, guard.y) = self.teleports[&(x, y)],
+        }
+    }
+
     fn is_visited(&self, x: i64, y: i64) -> bool {
         if !self.in_bounds(x, y) {
             panic!("unimplemented");
@@ -355,6 +450,8 @@ impl Map {
 
 struct GuardStateSpace {
     states: Vec<bool>,
+    /// The indices of the seen states so that resetting doesn't have to clear the whole space
+    seen: Vec<usize>,
     width: usize,
 }
 
@@ -362,75 +459,170 @@ impl GuardStateSpace {
     fn for_map(map: &Map) -> Self {
         Self {
             states: vec![false; map.tiles.len() * 4],
+            seen: vec![],
             width: map.width,
         }
     }
 
     fn reset(&mut self) {
-        self.states.fill(false);
+        for &index in &self.seen {
+            self.states[index] = false;
+        }
+        self.seen.clear();
+    }
+
+    fn index(&self, guard: &GuardState) -> usize {
+        4 * self.width * (guard.y as usize) + 4 * (guard.x as usize) + guard.direction.value()
     }
 
     fn mark_seen(&mut self, guard: &GuardState) {
-        self.states[4 * self.width * (guard.y as usize) + 4 * (guard.x as usize) + guard.direction.value()] = true;
+        let index = self.index(guard);
+        self.states[index] = true;
+        self.seen.push(index);
     }
 
     fn was_seen(&self, guard: &GuardState) -> bool {
-        self.states[4 * self.width * (guard.y as usize) + 4 * (guard.x as usize) + guard.direction.value()]
+        self.states[self.index(guard)]
+    }
+}
+
+/// For every cell and direction, how far the guard can walk that way before stopping in front of
+/// anything other than a free tile, or None if they walk straight off the map
+struct JumpTable {
+    distances: Vec<Option<u32>>,
+    width: usize,
+}
+
+impl JumpTable {
+    fn for_map(map: &Map) -> Self {
+        let mut jump_table = JumpTable {
+            distances: vec![None; map.tiles.len() * 4],
+            width: map.width,
+        };
+        for direction in [Direction::Up, Direction::Right, Direction::Down, Direction::Left] {
+            let (dx, dy) = direction.unit_vector();
+            // Visit the cells so that the next cell in the direction has always been done already
+            let xs = (0..map.width as i64).collect_vec();
+            let ys = (0..map.height as i64).collect_vec();
+            let xs = if dx > 0 { xs.into_iter().rev().collect_vec() } else { xs };
+            let ys = if dy > 0 { ys.into_iter().rev().collect_vec() } else { ys };
+            for &y in &ys {
+                for &x in &xs {
+                    let (next_x, next_y) = (x + dx, y + dy);
+                    let distance = if !map.in_bounds(next_x, next_y) {
+                        None
+                    } else if map.get_tile(next_x, next_y) != Tile::Free {
+                        Some(0)
+                    } else {
+                        jump_table.get(next_x, next_y, direction).map(|distance| distance + 1)
+                    };
+                    let index = jump_table.index(x, y, direction);
+                    jump_table.distances[index] = distance;
+                }
+            }
+        }
+        jump_table
+    }
+
+    fn index(&self, x: i64, y: i64, direction: Direction) -> usize {
+        4 * self.width * (y as usize) + 4 * (x as usize) + direction.value()
+    }
+
+    fn get(&self, x: i64, y: i64, direction: Direction) -> Option<u32> {
+        self.distances[self.index(x, y, direction)]
+    }
+
+    /// How far the guard walks before stopping, with an extra obstruction which isn't in the table
+    fn jump(&self, guard: &GuardState, obstruction: Option<(i64, i64)>) -> Option<u32> {
+        let distance = self.get(guard.x, guard.y, guard.direction);
+        let Some(obstruction) = obstruction else {
+            return distance;
+        };
+        let (dx, dy) = guard.direction.unit_vector();
+        let (offset_x, offset_y) = (obstruction.0 - guard.x, obstruction.1 - guard.y);
+        // Only an obstruction straight ahead, and closer than the next stop, gets in the way
+        let obstruction_distance = if dx == 0 && offset_x == 0 {
+            offset_y * dy
+        } else if dy == 0 && offset_y == 0 {
+            offset_x * dx
+        } else {
+            return distance;
+        };
+        if obstruction_distance >= 1 && distance.is_none_or(|distance| obstruction_distance - 1 <= distance as i64) {
+            Some(obstruction_distance as u32 - 1)
+        } else {
+            distance
+        }
     }
 }
 
-fn preprocess(input: &str) -> (Map, GuardState) {
+fn preprocess(input: &str) -> (Map, Vec<GuardState>) {
     let lines = input.lines().collect_vec();
     let height = lines.len();
     let width = lines[0].len(); // char == byte for this problem
     let mut map = Map {
         tiles: vec![Tile::Free; width * height],
+        teleports: HashMap::new(),
         visited: vec![false; width * height],
         width,
         height
     };
-    let mut guard_x = -1i64;
-    let mut guard_y = -1i64;
+    let mut guards = vec![];
+    let mut teleports = HashMap::<u8, Vec<(i64, i64)>>::new();
     for (y, line) in lines.into_iter().enumerate() {
         for (x, c) in line.char_indices() {
-            if c == '^' {
-                guard_x = x as i64;
-                guard_y = y as i64;
+            let (x, y) = (x as i64, y as i64);
+            if let Some(direction) = Direction::from_guard_symbol(c) {
+                guards.push(GuardState { x, y, direction });
+                continue;
+            }
+            let tile = Tile::from_symbol(c).unwrap_or_else(|| panic!("Unknown tile: {c}"));
+            if let Tile::Teleport(id) = tile {
+                teleports.entry(id).or_default().push((x, y));
             }
-            map.set_tile(x as i64, y as i64, if c == '#' {
-                Tile::Obstacle
-            } else {
-                Tile::Free
-            });
+            map.set_tile(x, y, tile);
         }
     }
-    if guard_x < 0 {
+    if guards.is_empty() {
         panic!("Did not find guard position");
     }
+    for (id, positions) in teleports {
+        let &[first, second] = positions.as_slice() else {
+            panic!("Teleport {id} should appear exactly twice");
+        };
+        map.teleports.insert(first, second);
+        map.teleports.insert(second, first);
+    }
 
-    map.visit_tile(guard_x, guard_y);
-    (map, GuardState {
-        x: guard_x,
-        y: guard_y,
-        direction: Direction::Up,
-    })
+    for guard in &guards {
+        map.visit_tile(guard.x, guard.y);
+    }
+    (map, guards)
 }
 
-fn get_guard_expected_visit_map(input: &str) -> (Map, GuardState) {
-    let (mut map, original_guard) = preprocess(input);
-
-    let mut guard = original_guard.clone();
-    while map.in_bounds(guard.x, guard.y) {
-        let looking_at = guard.looking_at();
-        let looking_at = map.get_tile(looking_at.0, looking_at.1);
-        match looking_at {
-            Tile::Free => guard.step(),
-            Tile::Obstacle => guard.turn_right(),
+/// Walks each guard in turn until they leave the map (or get stuck in a loop), calling `on_step`
+/// with the state after each step
+fn walk_guard(input: &str, mut on_step: impl FnMut(&Map, &GuardState)) -> (Map, Vec<GuardState>) {
+    let (mut map, original_guards) = preprocess(input);
+    let mut state_space = GuardStateSpace::for_map(&map);
+
+    for original_guard in &original_guards {
+        state_space.reset();
+        let mut guard = original_guard.clone();
+        on_step(&map, &guard);
+        while map.in_bounds(guard.x, guard.y) && !state_space.was_seen(&guard) {
+            state_space.mark_seen(&guard);
+            map.move_guard(&mut guard, None);
+            map.visit_tile(guard.x, guard.y);
+            on_step(&map, &guard);
         }
-        map.visit_tile(guard.x, guard.y);
     }
 
-    (map, original_guard)
+    (map, original_guards)
+}
+
+fn get_guard_expected_visit_map(input: &str) -> (Map, Vec<GuardState>) {
+    walk_guard(input, |_, _| {})
 }
 
 fn get_distinct_guard_positions(input: &str) -> u64 {
@@ -442,64 +634,250 @@ fn get_distinct_guard_positions(input: &str) -> u64 {
         .count() as u64
 }
 
+/// Jumps the guard from stop to stop, the state space only needs to hold the states at the stops
 fn check_for_loop(
     map: &Map,
+    jump_table: &JumpTable,
     state_space: &mut GuardStateSpace,
-    mut guard: GuardState
+    mut guard: GuardState,
+    obstruction: (i64, i64)
 ) -> bool {
-    while map.in_bounds(guard.x, guard.y) {
+    // If the walk ends at a tile rather than the edge of the map then the tile decides what's next
+    while let Some(distance) = jump_table.jump(&guard, Some(obstruction)) {
+        let (dx, dy) = guard.direction.unit_vector();
+        guard.x += dx * distance as i64;
+        guard.y += dy * distance as i64;
+
         // If we have returned to a previously seen state then we have a loop
         if state_space.was_seen(&guard) {
             return true;
         }
         // Mark the state as seen
         state_space.mark_seen(&guard);
-
-        // Propagate the state forward
-        let looking_at = guard.looking_at();
-        let looking_at = map.get_tile(looking_at.0, looking_at.1);
-        match looking_at {
-            Tile::Free => guard.step(),
-            Tile::Obstacle => guard.turn_right(),
-        }
+        map.move_guard(&mut guard, Some(obstruction));
     }
 
     // We escaped the map: therefore no loop
     false
 }
 
+fn get_obstruction_candidates(map: &Map, original_guards: &[GuardState]) -> Vec<(i64, i64)> {
+    // Skip over any spots which we don't expect to visit: no point checking those
+    // Also skip over the guards' initial positions - we aren't allowed to place an obstacle there
+    // And the obstruction can only replace a free tile
+    (0..map.height as i64)
+        .cartesian_product(0..map.width as i64)
+        .map(|(y, x)| (x, y))
+        .filter(|&(x, y)| map.is_visited(x, y) && map.get_tile(x, y) == Tile::Free)
+        .filter(|&(x, y)| original_guards.iter().all(|guard| !(x == guard.x && y == guard.y)))
+        .collect_vec()
+}
+
+/// The index of the first guard who gets stuck in a loop once the obstruction is placed
+fn find_looping_guard(
+    map: &Map,
+    jump_table: &JumpTable,
+    state_space: &mut GuardStateSpace,
+    original_guards: &[GuardState],
+    obstruction: (i64, i64)
+) -> Option<usize> {
+    original_guards.iter().position(|guard| {
+        state_space.reset();
+        check_for_loop(map, jump_table, state_space, guard.clone(), obstruction)
+    })
+}
+
+/// Counts the obstruction positions which would trap at least one of the guards
 fn get_potential_obstructions(input: &str) -> u64 {
-    let (mut map, original_guard_position) = get_guard_expected_visit_map(input);
-
-    let mut valid_spots = 0;
-    let mut guard_state_space = GuardStateSpace::for_map(&map);
-
-    for x in 0..map.width {
-        let x = x as i64;
-        for y in 0..map.height {
-            let y = y as i64;
-            // Skip over any spots which we don't expect to visit: no point checking those
-            // Also skip over the guard's initial position - we aren't allowed to place an obstacle there
-            if map.is_visited(x, y) && !(x == original_guard_position.x && y == original_guard_position.y) {
-                guard_state_space.reset(); // Start with a fresh state space
-
-                // Temporarily add an obstacle
-                map.set_tile(x, y, Tile::Obstacle);
-
-                // If we have a loop now then increment our counter
-                if check_for_loop(&map, &mut guard_state_space, original_guard_position.clone()) {
-                    valid_spots += 1;
+    let (map, original_guards) = get_guard_expected_visit_map(input);
+    let jump_table = JumpTable::for_map(&map);
+
+    get_obstruction_candidates(&map, &original_guards).into_par_iter()
+        .map_init(
+            // Each thread reuses its own state space
+            || GuardStateSpace::for_map(&map),
+            |state_space, obstruction| {
+                find_looping_guard(&map, &jump_table, state_space, &original_guards, obstruction).is_some()
+            }
+        )
+        .filter(|&is_loop| is_loop)
+        .count() as u64
+}
+
+/// A straight stretch of the guard's route, both ends are cells the guard stands on
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+struct Segment {
+    start: (i64, i64),
+    end: (i64, i64),
+    direction: Direction,
+}
+
+impl Display for Segment {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{},{} -> {},{} {:?}", self.start.0, self.start.1, self.end.0, self.end.1, self.direction)
+    }
+}
+
+/// Why an obstruction counts: the loop a guard gets stuck in once it's placed
+struct LoopWitness {
+    obstruction: (i64, i64),
+    /// The index of the guard who gets stuck
+    guard: usize,
+    /// The first state the guard is in twice, where the loop starts
+    entry: GuardState,
+    cycle: Vec<Segment>,
+}
+
+/// Jumps the guard from stop to stop, returning their route as segments and, if they end up
+/// going round in a loop, the index of the segment where the loop starts and the state the guard
+/// is in there
+///
+/// A segment ends wherever the guard stops in front of a tile, so walking through a one-way tile
+/// or a teleport starts a new segment even without a turn. Turning on the spot doesn't make a
+/// segment of its own.
+fn trace_route(
+    map: &Map,
+    jump_table: &JumpTable,
+    mut guard: GuardState,
+    obstruction: Option<(i64, i64)>
+) -> (Vec<Segment>, Option<(usize, GuardState)>) {
+    let mut segments: Vec<Segment> = vec![];
+    let mut segment_starts = HashMap::new();
+    loop {
+        if let Some(&loop_start) = segment_starts.get(&(guard.x, guard.y, guard.direction)) {
+            return (segments, Some((loop_start, guard)));
+        }
+        segment_starts.insert((guard.x, guard.y, guard.direction), segments.len());
+
+        let (dx, dy) = guard.direction.unit_vector();
+        let start = (guard.x, guard.y);
+        match jump_table.jump(&guard, obstruction) {
+            Some(distance) => {
+                guard.x += dx * distance as i64;
+                guard.y += dy * distance as i64;
+                if distance > 0 {
+                    segments.push(Segment { start, end: (guard.x, guard.y), direction: guard.direction });
                 }
+                map.move_guard(&mut guard, obstruction);
+            },
+            None => {
+                // Walk to the last cell before the edge of the map
+                while map.in_bounds(guard.x + dx, guard.y + dy) {
+                    guard.x += dx;
+                    guard.y += dy;
+                }
+                if (guard.x, guard.y) != start {
+                    segments.push(Segment { start, end: (guard.x, guard.y), direction: guard.direction });
+                }
+                return (segments, None);
+            },
+        }
+    }
+}
 
-                // Remove the obstacle
-                map.set_tile(x, y, Tile::Free);
+/// Each guard's full route, with no obstruction added
+fn get_guard_routes(input: &str) -> Vec<Vec<Segment>> {
+    let (map, guards) = preprocess(input);
+    let jump_table = JumpTable::for_map(&map);
+    guards.into_iter()
+        .map(|guard| trace_route(&map, &jump_table, guard, None).0)
+        .collect()
+}
+
+fn get_loop_witnesses(input: &str) -> Vec<LoopWitness> {
+    let (map, original_guards) = get_guard_expected_visit_map(input);
+    let jump_table = JumpTable::for_map(&map);
+
+    get_obstruction_candidates(&map, &original_guards).into_par_iter()
+        .map_init(
+            || GuardStateSpace::for_map(&map),
+            |state_space, obstruction| {
+                let guard = find_looping_guard(&map, &jump_table, state_space, &original_guards, obstruction)?;
+                let (segments, loop_start) = trace_route(&map, &jump_table, original_guards[guard].clone(), Some(obstruction));
+                // The cycle is empty for a guard boxed in on every side, who just turns on the spot
+                let (loop_start, entry) = loop_start.unwrap();
+                let cycle = segments[loop_start..].to_vec();
+                Some(LoopWitness { obstruction, guard, entry, cycle })
             }
-        }
+        )
+        .flatten()
+        .collect()
+}
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum DisplayTile {
+    Free,
+    Obstacle,
+    Deflector,
+    OneWay(Direction),
+    Teleport,
+    Visited,
+    Guard(Direction),
+}
+
+impl DisplayTile {
+    fn legend() -> Legend<DisplayTile> {
+        Legend::new()
+            .with(DisplayTile::Free, '.', Colour::BrightBlack, "free")
+            .with(DisplayTile::Obstacle, '#', Colour::White, "obstacle")
+            .with(DisplayTile::Deflector, '*', Colour::Magenta, "deflector")
+            .with(DisplayTile::OneWay(Direction::Up), '↑', Colour::Cyan, "one way")
+            .with(DisplayTile::OneWay(Direction::Right), '→', Colour::Cyan, "")
+            .with(DisplayTile::OneWay(Direction::Down), '↓', Colour::Cyan, "")
+            .with(DisplayTile::OneWay(Direction::Left), '←', Colour::Cyan, "")
+            .with(DisplayTile::Teleport, 'O', Colour::Blue, "teleport")
+            .with(DisplayTile::Visited, 'X', Colour::Yellow, "visited")
+            .with(DisplayTile::Guard(Direction::Up), '^', Colour::Red, "guard")
+            .with(DisplayTile::Guard(Direction::Right), '>', Colour::Red, "")
+            .with(DisplayTile::Guard(Direction::Down), 'v', Colour::Red, "")
+            .with(DisplayTile::Guard(Direction::Left), '<', Colour::Red, "")
     }
+}
 
-    valid_spots
+/// A snapshot of the guard part way through their walk
+struct GuardView<'a> {
+    map: &'a Map,
+    guard: &'a GuardState,
 }
 
+impl Grid for GuardView<'_> {
+    type Tile = DisplayTile;
+
+    fn width(&self) -> usize {
+        self.map.width
+    }
+
+    fn height(&self) -> usize {
+        self.map.height
+    }
+
+    fn tile(&self, x: usize, y: usize) -> DisplayTile {
+        let (x, y) = (x as i64, y as i64);
+        if x == self.guard.x && y == self.guard.y {
+            DisplayTile::Guard(self.guard.direction)
+        } else {
+            match self.map.get_tile(x, y) {
+                Tile::Obstacle => DisplayTile::Obstacle,
+                Tile::Deflector => DisplayTile::Deflector,
+                Tile::OneWay(direction) => DisplayTile::OneWay(direction),
+                Tile::Teleport(_) => DisplayTile::Teleport,
+                Tile::Free if self.map.is_visited(x, y) => DisplayTile::Visited,
+                Tile::Free => DisplayTile::Free,
+            }
+        }
+    }
+}
+
+fn visualise_guard_walk(input: &str, renderer: &mut Renderer<DisplayTile>) {
+    let mut last_guard = None;
+    let (map, _) = walk_guard(input, |map, guard| {
+        renderer.frame_with(|| GuardView { map, guard });
+        last_guard = Some(guard.clone());
+    });
+    if let Some(guard) = &last_guard {
+        renderer.final_frame(&GuardView { map: &map, guard });
+    }
+}
 
 #[test]
 fn test_part1() {
@@ -536,3 +914,149 @@ fn test_part2() {
                )
     );
 }
+
+#[test]
+fn test_jump_table() {
+    let (map, guards) = preprocess(
+        r"....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#..."
+    );
+    let jump_table = JumpTable::for_map(&map);
+    let guard = &guards[0];
+    assert_eq!(Some(5), jump_table.jump(guard, None));
+    assert_eq!(Some(2), jump_table.jump(guard, Some((4, 3))));
+    assert_eq!(Some(5), jump_table.jump(guard, Some((4, 7))));
+    assert_eq!(None, jump_table.get(4, 6, Direction::Down));
+    assert_eq!(Some(2), jump_table.jump(&GuardState { x: 4, y: 6, direction: Direction::Down }, Some((4, 9))));
+    assert_eq!(Some(2), jump_table.get(1, 9, Direction::Up));
+}
+
+#[test]
+fn test_route_and_loop_witnesses() {
+    let input = r"....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#...";
+    let route = &get_guard_routes(input)[0];
+    assert_eq!(
+        vec![
+            "4,6 -> 4,1 Up",
+            "4,1 -> 8,1 Right",
+            "8,1 -> 8,6 Down",
+            "8,6 -> 2,6 Left",
+            "2,6 -> 2,4 Up",
+            "2,4 -> 6,4 Right",
+            "6,4 -> 6,8 Down",
+            "6,8 -> 1,8 Left",
+            "1,8 -> 1,7 Up",
+            "1,7 -> 7,7 Right",
+            "7,7 -> 7,9 Down",
+        ],
+        route.iter().map(|segment| segment.to_string()).collect_vec()
+    );
+
+    let witnesses = get_loop_witnesses(input);
+    assert_eq!(
+        vec![(3, 6), (6, 7), (7, 7), (1, 8), (3, 8), (7, 9)],
+        witnesses.iter().map(|witness| witness.obstruction).collect_vec()
+    );
+    // The one in the puzzle's first picture, right next to the guard, sends them back to the start
+    let witness = &witnesses[0];
+    assert_eq!(GuardState { x: 4, y: 6, direction: Direction::Up }, witness.entry);
+    assert_eq!(
+        vec!["4,6 -> 4,1 Up", "4,1 -> 8,1 Right", "8,1 -> 8,6 Down", "8,6 -> 4,6 Left"],
+        witness.cycle.iter().map(|segment| segment.to_string()).collect_vec()
+    );
+    // Each cycle ends where it started
+    for witness in &witnesses {
+        assert_eq!(witness.cycle[0].start, witness.cycle.last().unwrap().end);
+    }
+
+    // Turning on the spot doesn't leave an empty segment behind
+    let input = r".#.
+.^.
+...";
+    assert_eq!(
+        vec!["1,1 -> 2,1 Right"],
+        get_guard_routes(input)[0].iter().map(|segment| segment.to_string()).collect_vec()
+    );
+    // Nor does walking off the map from the edge
+    assert!(get_guard_routes(".^.")[0].is_empty());
+
+    // With the obstruction to their right, a boxed in guard never moves
+    let input = r".#.
+#^.
+.#.";
+    let witnesses = get_loop_witnesses(input);
+    assert_eq!(vec![(2, 1)], witnesses.iter().map(|witness| witness.obstruction).collect_vec());
+    assert_eq!(GuardState { x: 1, y: 1, direction: Direction::Up }, witnesses[0].entry);
+    assert!(witnesses[0].cycle.is_empty());
+}
+
+#[test]
+fn test_extended_tiles() {
+    // The first guard turns left at the deflector, the second teleports to the first's row
+    assert_eq!(10,
+               get_distinct_guard_positions(
+                   r"*....
+.....
+^...1
+1...<"
+               )
+    );
+    // The one way tile can be walked onto going right
+    assert_eq!(5,
+               get_distinct_guard_positions(
+                   r".....
+.#...
+..e..
+.^..."
+               )
+    );
+    // But it's an obstacle when going left, and the guard turns up and off the map
+    assert_eq!(1, get_distinct_guard_positions(r">w."));
+    // Teleporting round in a loop forever without any obstruction
+    assert_eq!(4, get_distinct_guard_positions(r"1.>.1"));
+
+    // Jumping between stops finds the same loops as walking each guard a step at a time
+    for input in [
+        "*....\n.....\n^...1\n1...<",
+        "..#...\n.....#\n.w....\n.^.e..\n#.....\n....#.",
+        "....#.....\n...1.....#\n..........\n..#....*..\n.......#..\n..........\n.#..^.....\n......1.#.\n#.........\n......#...",
+        "....#.....\n.........#\n..........\n..#.......\n.......#..\n..........\n.#..^....<\n........#.\n#.........\n......#...",
+    ] {
+        let (map, guards) = preprocess(input);
+        let jump_table = JumpTable::for_map(&map);
+        let mut state_space = GuardStateSpace::for_map(&map);
+        for obstruction in get_obstruction_candidates(&get_guard_expected_visit_map(input).0, &guards) {
+            for guard in &guards {
+                let mut stepped_guard = guard.clone();
+                let mut seen = std::collections::HashSet::new();
+                let mut is_loop = false;
+                while map.in_bounds(stepped_guard.x, stepped_guard.y) {
+                    if !seen.insert(stepped_guard.clone()) {
+                        is_loop = true;
+                        break;
+                    }
+                    map.move_guard(&mut stepped_guard, Some(obstruction));
+                }
+                state_space.reset();
+                assert_eq!(is_loop, check_for_loop(&map, &jump_table, &mut state_space, guard.clone(), obstruction));
+            }
+        }
+    }
+}
@@ -83,71 +83,491 @@ equations could possibly be true. What is their total calibration result?
  */
 
 use itertools::Itertools;
-use std::fs;
+use advent_of_code2024::runner::Runner;
+use num::{BigUint, CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, Zero};
+use std::fmt::{Debug, Display};
+use std::ops::{BitXor, Rem};
+use std::str::FromStr;
 
 fn main() {
-    let input_file = fs::read_to_string("./inputs/07_bridge_repair.txt").unwrap();
-    println!("{}", get_sum_possibly_true_equations(&input_file, false));
-    println!("{}", get_sum_possibly_true_equations(&input_file, true));
+    let runner = Runner::from_args("07_bridge_repair").solver_version("2");
+    if runner.has_flag("--big") {
+        // Every value is arbitrary precision, for equations too big for a u64
+        let input_file = runner.read_input();
+        if let Some(operators) = runner.option("--operators") {
+            let operators = parse_operators::<BigUint>(operators).unwrap();
+            let operators = operators.iter().map(|operator| operator.as_ref()).collect_vec();
+            println!("{}", get_sum_possibly_true_equations(&input_file, &operators));
+            return;
+        }
+        runner.run_part(1, &input_file, || get_sum_possibly_true_equations(&input_file, BIG_PART1_OPERATORS));
+        runner.run_part(2, &input_file, || get_sum_possibly_true_equations(&input_file, BIG_PART2_OPERATORS));
+        return;
+    }
+    if let Some(operators) = runner.option("--operators") {
+        let operators = parse_operators::<u64>(operators).unwrap();
+        let operators = operators.iter().map(|operator| operator.as_ref()).collect_vec();
+        println!("{}", get_sum_possibly_true_equations(&runner.read_input(), &operators));
+        return;
+    }
+    if runner.has_flag("--explain") || runner.option("--explain").is_some() {
+        for equation in preprocess::<u64>(&runner.read_input()) {
+            match runner.option("--explain") {
+                Some("all") => for solution in find_all_solutions(&equation, PART2_OPERATORS).unwrap() {
+                    println!("{}", format_solution(&equation, &solution));
+                },
+                Some("count") => println!("{}: {}", equation.test_value, count_solutions(&equation, PART2_OPERATORS).unwrap()),
+                _ => if let Some(solution) = find_solution(&equation, PART2_OPERATORS).unwrap() {
+                    println!("{}", format_solution(&equation, &solution));
+                },
+            }
+        }
+        return;
+    }
+
+    runner.run_parts(
+        |input| get_sum_possibly_true_equations(input, PART1_OPERATORS),
+        |input| get_sum_possibly_true_equations(input, PART2_OPERATORS),
+        |runner| get_sums_possibly_true_equations(runner.input_lines())
+    );
+}
+
+/// The numbers an equation can be written in, a u64 or a `BigUint`
+///
+/// `checked_add` and `checked_mul` only fail by overflowing, `checked_sub` and `checked_div` fail
+/// when the result is negative or the divisor is zero.
+trait Value: Clone + Ord + Debug + Display + FromStr + From<u64> + Zero + CheckedAdd + CheckedSub + CheckedMul
+    + CheckedDiv + Rem<Output = Self> + BitXor<Output = Self> {}
+
+impl<N> Value for N where N: Clone + Ord + Debug + Display + FromStr + From<u64> + Zero + CheckedAdd + CheckedSub
+    + CheckedMul + CheckedDiv + Rem<Output = Self> + BitXor<Output = Self> {}
+
+fn is_multiple_of<N: Value>(a: &N, b: &N) -> bool {
+    (a.clone() % b.clone()).is_zero()
 }
 
 #[derive(Debug)]
-struct Equation {
-    test_value: u64,
-    terms: Vec<u64>,
+struct Equation<N> {
+    test_value: N,
+    terms: Vec<N>,
 }
 
-fn preprocess(input: &str) -> Vec<Equation> {
-    input.lines().filter_map(|line| {
-        let Some((test_value, terms)) = line.split_once(": ") else { return None; };
+fn parse_equation<N: Value>(line: &str) -> Option<Equation<N>> {
+    let (test_value, terms) = line.split_once(": ")?;
 
-        let test_value = test_value.parse::<u64>().expect("Couldn't parse test value");
-        let terms = terms.split_whitespace().map(|term| term.parse::<u64>().expect("Couldn't parse term")).collect_vec();
+    // A u64 can't hold every number, which is what --big is for
+    let parse = |number: &str| number.parse::<N>()
+        .unwrap_or_else(|_| panic!("Couldn't parse {number}, if it's too big then try --big"));
+    let test_value = parse(test_value);
+    let terms = terms.split_whitespace().map(parse).collect_vec();
 
-        Some(Equation {
-            test_value,
-            terms
-        })
-    }).collect_vec()
+    Some(Equation {
+        test_value,
+        terms
+    })
+}
+
+fn preprocess<N: Value>(input: &str) -> Vec<Equation<N>> {
+    input.lines().filter_map(parse_equation).collect_vec()
+}
+
+/// A value got too big for its type part way through solving an equation
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+struct Overflow;
+
+/// The result of applying an operator
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Application<N> {
+    Value(N),
+    /// The operator doesn't give a value (e.g. it's negative or a division by zero)
+    Undefined,
+    Overflow,
+}
+
+/// What undoing an operator tells us about its left operand
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Inversion<N> {
+    /// More than one left operand could give the result (or it's too hard to work out)
+    NotInvertible,
+    /// No left operand gives the result
+    NoSolution,
+    Solution(N),
+    Overflow,
+}
+
+/// A binary operator that can appear between the terms of an equation
+trait Operator<N> {
+    fn symbol(&self) -> String;
+
+    /// `a op b`
+    fn apply(&self, a: &N, b: &N) -> Application<N>;
+
+    /// The `a` for which `a op b == result`
+    fn invert(&self, _result: &N, _b: &N) -> Inversion<N> {
+        Inversion::NotInvertible
+    }
+
+    /// Whether `a op b >= a` for every positive `b`, so that a search can give up as soon as it
+    /// goes past the test value
+    fn never_decreases(&self) -> bool {
+        false
+    }
+}
+
+struct Add;
+
+impl<N: Value> Operator<N> for Add {
+    fn symbol(&self) -> String {
+        "+".to_string()
+    }
+
+    fn apply(&self, a: &N, b: &N) -> Application<N> {
+        a.checked_add(b).map_or(Application::Overflow, Application::Value)
+    }
+
+    fn invert(&self, result: &N, b: &N) -> Inversion<N> {
+        result.checked_sub(b).map_or(Inversion::NoSolution, Inversion::Solution)
+    }
+
+    fn never_decreases(&self) -> bool {
+        true
+    }
+}
+
+struct Multiply;
+
+impl<N: Value> Operator<N> for Multiply {
+    fn symbol(&self) -> String {
+        "*".to_string()
+    }
+
+    fn apply(&self, a: &N, b: &N) -> Application<N> {
+        a.checked_mul(b).map_or(Application::Overflow, Application::Value)
+    }
+
+    fn invert(&self, result: &N, b: &N) -> Inversion<N> {
+        if b.is_zero() {
+            // Anything times zero is zero
+            if result.is_zero() { Inversion::NotInvertible } else { Inversion::NoSolution }
+        } else if is_multiple_of(result, b) {
+            Inversion::Solution(result.clone() / b.clone())
+        } else {
+            Inversion::NoSolution
+        }
+    }
+
+    fn never_decreases(&self) -> bool {
+        true
+    }
+}
+
+struct Subtract;
+
+impl<N: Value> Operator<N> for Subtract {
+    fn symbol(&self) -> String {
+        "-".to_string()
+    }
+
+    fn apply(&self, a: &N, b: &N) -> Application<N> {
+        a.checked_sub(b).map_or(Application::Undefined, Application::Value)
+    }
+
+    fn invert(&self, result: &N, b: &N) -> Inversion<N> {
+        result.checked_add(b).map_or(Inversion::Overflow, Inversion::Solution)
+    }
+}
+
+/// Integer division, rounding down
+struct Divide;
+
+impl<N: Value> Operator<N> for Divide {
+    fn symbol(&self) -> String {
+        "/".to_string()
+    }
+
+    fn apply(&self, a: &N, b: &N) -> Application<N> {
+        a.checked_div(b).map_or(Application::Undefined, Application::Value)
+    }
+}
+
+struct Xor;
+
+impl<N: Value> Operator<N> for Xor {
+    fn symbol(&self) -> String {
+        "^".to_string()
+    }
+
+    fn apply(&self, a: &N, b: &N) -> Application<N> {
+        Application::Value(a.clone() ^ b.clone())
+    }
+
+    fn invert(&self, result: &N, b: &N) -> Inversion<N> {
+        Inversion::Solution(result.clone() ^ b.clone())
+    }
+}
+
+/// Writes the digits of `b` after the digits of `a`, in the given base
+struct Concat {
+    base: u64,
 }
 
-fn concat(a: u64, b: u64) -> u64 {
-    let mut multiplier = 1u64;
-    while multiplier <= b {
-        multiplier *= 10;
+impl Concat {
+    /// The power of the base that shifts `a` left by the number of digits in `b`, None if it
+    /// overflows
+    fn multiplier<N: Value>(&self, b: &N) -> Option<N> {
+        let base = N::from(self.base);
+        let mut multiplier = base.clone();
+        while multiplier <= *b {
+            multiplier = multiplier.checked_mul(&base)?;
+        }
+        Some(multiplier)
+    }
+}
+
+impl<N: Value> Operator<N> for Concat {
+    fn symbol(&self) -> String {
+        if self.base == 10 {
+            "||".to_string()
+        } else {
+            format!("||{}", self.base)
+        }
+    }
+
+    fn apply(&self, a: &N, b: &N) -> Application<N> {
+        self.multiplier(b)
+            .and_then(|multiplier| a.checked_mul(&multiplier))
+            .and_then(|shifted| shifted.checked_add(b))
+            .map_or(Application::Overflow, Application::Value)
+    }
+
+    fn invert(&self, result: &N, b: &N) -> Inversion<N> {
+        // Concatenating never gives a smaller number, so there's no need to work out the multiplier
+        if result < b {
+            return Inversion::NoSolution;
+        }
+        let Some(multiplier) = self.multiplier(b) else {
+            return Inversion::Overflow;
+        };
+        // Only a result ending in the digits of b can be undone
+        match result.checked_sub(b) {
+            Some(shifted) if is_multiple_of(&shifted, &multiplier) => Inversion::Solution(shifted / multiplier),
+            _ => Inversion::NoSolution,
+        }
     }
 
-    a * multiplier + b
+    fn never_decreases(&self) -> bool {
+        true
+    }
 }
 
-fn is_equation_possibly_true(equation: &Equation, allow_concat: bool) -> bool {
-    // This solution relies on this assumption (i.e. the result of the equation is never decreasing)
-    // Though it only uses this assumption as an optimization
-    assert!(equation.terms.iter().all(|x| *x > 0));
+const PART1_OPERATORS: &[&dyn Operator<u64>] = &[&Multiply, &Add];
+const PART2_OPERATORS: &[&dyn Operator<u64>] = &[&Multiply, &Add, &Concat { base: 10 }];
+const BIG_PART1_OPERATORS: &[&dyn Operator<BigUint>] = &[&Multiply, &Add];
+const BIG_PART2_OPERATORS: &[&dyn Operator<BigUint>] = &[&Multiply, &Add, &Concat { base: 10 }];
+
+/// Parses a comma separated list of operator symbols, e.g. `+,*,||` or `-,^,||2`
+fn parse_operators<N: Value>(operators: &str) -> Result<Vec<Box<dyn Operator<N>>>, String> {
+    operators.split(',')
+        .map(|symbol| -> Result<Box<dyn Operator<N>>, String> {
+            match symbol {
+                "+" => Ok(Box::new(Add)),
+                "*" => Ok(Box::new(Multiply)),
+                "-" => Ok(Box::new(Subtract)),
+                "/" => Ok(Box::new(Divide)),
+                "^" => Ok(Box::new(Xor)),
+                "||" => Ok(Box::new(Concat { base: 10 })),
+                _ => match symbol.strip_prefix("||").and_then(|base| base.parse::<u64>().ok()) {
+                    Some(base) if base >= 2 => Ok(Box::new(Concat { base })),
+                    _ => Err(format!("Unknown operator: {symbol}")),
+                },
+            }
+        })
+        .collect()
+}
 
-    fn helper(target: u64, result: u64, remaining_terms: &[u64], allow_concat: bool) -> bool {
-        if remaining_terms.is_empty() {
-            return target == result;
+/// Works backwards from the test value, un-applying operators from the last term to the first, so
+/// only operators which could have produced the value are ever tried
+struct Solver<'a, 'b, N> {
+    operators: &'b [&'a dyn Operator<N>],
+    /// Whether the value can only grow from term to term, so that a forward search can stop early
+    can_prune: bool,
+    /// Whether any branch of the search was abandoned because a value overflowed
+    overflowed: bool,
+    /// The operators chosen so far, from the last term backwards
+    suffix: Vec<&'a dyn Operator<N>>,
+    on_solution: &'b mut dyn FnMut(&[&'a dyn Operator<N>]) -> bool,
+}
+
+impl<'a, N: Value> Solver<'a, '_, N> {
+    /// Returns false once `on_solution` has asked to stop
+    fn backward(&mut self, target: &N, terms: &[N]) -> bool {
+        let (last_term, prefix) = terms.split_last().unwrap();
+        if prefix.is_empty() {
+            return target != last_term || self.report(&[]);
         }
-        if result > target {
-            return false;
+        let operators = self.operators;
+        for &operator in operators {
+            self.suffix.push(operator);
+            let keep_going = match operator.invert(target, last_term) {
+                Inversion::Solution(previous_target) => self.backward(&previous_target, prefix),
+                Inversion::NoSolution => true,
+                Inversion::Overflow => {
+                    self.overflowed = true;
+                    true
+                },
+                Inversion::NotInvertible => {
+                    // Nothing for it but to try every value the rest of the terms can make
+                    let limit = self.can_prune.then_some(target);
+                    let is_solution = |result: &N| operator.apply(result, last_term) == Application::Value(target.clone());
+                    self.forward(&prefix[0], &prefix[1..], &mut vec![], &is_solution, limit)
+                },
+            };
+            self.suffix.pop();
+            if !keep_going {
+                return false;
+            }
         }
-        // Recurse trying both addition and multiplication
-        // We try multiplication first as it's going to get us to bigger numbers quicker
-        helper(target, result * remaining_terms[0], &remaining_terms[1..], allow_concat)
-            || helper(target, result + remaining_terms[0], &remaining_terms[1..], allow_concat)
-            || (allow_concat && helper(target, concat(result, remaining_terms[0]), &remaining_terms[1..], allow_concat))
+        true
+    }
+
+    fn forward(
+        &mut self,
+        result: &N,
+        remaining_terms: &[N],
+        prefix: &mut Vec<&'a dyn Operator<N>>,
+        is_solution: &dyn Fn(&N) -> bool,
+        limit: Option<&N>
+    ) -> bool {
+        if limit.is_some_and(|limit| result > limit) {
+            return true;
+        }
+        let Some((term, remaining_terms)) = remaining_terms.split_first() else {
+            return !is_solution(result) || self.report(prefix);
+        };
+        let operators = self.operators;
+        for &operator in operators {
+            let result = match operator.apply(result, term) {
+                Application::Value(result) => result,
+                Application::Undefined => continue,
+                Application::Overflow => {
+                    self.overflowed = true;
+                    continue;
+                },
+            };
+            prefix.push(operator);
+            let keep_going = self.forward(&result, remaining_terms, prefix, is_solution, limit);
+            prefix.pop();
+            if !keep_going {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn report(&mut self, prefix: &[&'a dyn Operator<N>]) -> bool {
+        let operators = prefix.iter().chain(self.suffix.iter().rev()).copied().collect_vec();
+        (self.on_solution)(&operators)
+    }
+}
+
+/// Calls `on_solution` with each sequence of operators which makes the equation true, until it
+/// returns false. The nth operator goes between the nth and n+1th terms.
+///
+/// Fails if a value overflowed, as there could have been solutions which weren't visited.
+fn visit_solutions<'a, N: Value>(
+    equation: &Equation<N>,
+    operators: &[&'a dyn Operator<N>],
+    mut on_solution: impl FnMut(&[&'a dyn Operator<N>]) -> bool
+) -> Result<(), Overflow> {
+    if equation.terms.is_empty() {
+        return Ok(());
+    }
+    let mut solver = Solver {
+        operators,
+        can_prune: operators.iter().all(|operator| operator.never_decreases())
+            && equation.terms.iter().all(|term| !term.is_zero()),
+        overflowed: false,
+        suffix: vec![],
+        on_solution: &mut on_solution,
+    };
+    solver.backward(&equation.test_value, &equation.terms);
+    if solver.overflowed { Err(Overflow) } else { Ok(()) }
+}
+
+fn find_solution<'a, N: Value>(
+    equation: &Equation<N>,
+    operators: &[&'a dyn Operator<N>]
+) -> Result<Option<Vec<&'a dyn Operator<N>>>, Overflow> {
+    let mut solution = None;
+    let result = visit_solutions(equation, operators, |operators| {
+        solution = Some(operators.to_vec());
+        false
+    });
+    // A solution is a solution, whatever overflowed while looking for it
+    match solution {
+        Some(solution) => Ok(Some(solution)),
+        None => result.map(|_| None),
+    }
+}
+
+fn find_all_solutions<'a, N: Value>(
+    equation: &Equation<N>,
+    operators: &[&'a dyn Operator<N>]
+) -> Result<Vec<Vec<&'a dyn Operator<N>>>, Overflow> {
+    let mut solutions = vec![];
+    visit_solutions(equation, operators, |operators| {
+        solutions.push(operators.to_vec());
+        true
+    })?;
+    Ok(solutions)
+}
+
+fn count_solutions<N: Value>(equation: &Equation<N>, operators: &[&dyn Operator<N>]) -> Result<u64, Overflow> {
+    let mut count = 0;
+    visit_solutions(equation, operators, |_| {
+        count += 1;
+        true
+    })?;
+    Ok(count)
+}
+
+/// e.g. `190 = 10 * 19`, the operators are evaluated left to right
+fn format_solution<N: Value>(equation: &Equation<N>, operators: &[&dyn Operator<N>]) -> String {
+    let mut expression = equation.terms[0].to_string();
+    for (operator, term) in operators.iter().zip(&equation.terms[1..]) {
+        expression.push_str(&format!(" {} {term}", operator.symbol()));
     }
+    format!("{} = {expression}", equation.test_value)
+}
 
-    helper(equation.test_value, 0, &equation.terms, allow_concat)
+fn is_equation_possibly_true<N: Value>(equation: &Equation<N>, operators: &[&dyn Operator<N>]) -> Result<bool, Overflow> {
+    find_solution(equation, operators).map(|solution| solution.is_some())
 }
 
-fn get_sum_possibly_true_equations(input: &str, allow_concat: bool) -> u64 {
+/// Panics if an equation can't be checked without overflowing, rather than giving a wrong answer
+fn get_sum_possibly_true_equations<N: Value>(input: &str, operators: &[&dyn Operator<N>]) -> N {
     preprocess(input)
         .into_iter()
-        .filter(|eq| is_equation_possibly_true(eq, allow_concat))
-        .map(|x| x.test_value)
-        .sum()
+        .filter(|eq| is_equation_possibly_true(eq, operators)
+            .unwrap_or_else(|_| panic!("Overflowed checking {eq:?}, try --big")))
+        .fold(N::zero(), |sum, eq| sum.checked_add(&eq.test_value).expect("Overflowed the sum, try --big"))
+}
+
+/// Computes the sums without and with concatenation in a single pass, an equation at a time
+fn get_sums_possibly_true_equations(input_lines: impl Iterator<Item=impl AsRef<str>>) -> (u64, u64) {
+    let is_possibly_true = |eq: &Equation<u64>, operators| is_equation_possibly_true(eq, operators)
+        .unwrap_or_else(|_| panic!("Overflowed checking {eq:?}, try --big"));
+    input_lines
+        .filter_map(|line| parse_equation(line.as_ref()))
+        .fold((0, 0), |(sum, sum_with_concat), eq| {
+            if is_possibly_true(&eq, PART1_OPERATORS) {
+                (sum + eq.test_value, sum_with_concat + eq.test_value)
+            } else if is_possibly_true(&eq, PART2_OPERATORS) {
+                (sum, sum_with_concat + eq.test_value)
+            } else {
+                (sum, sum_with_concat)
+            }
+        })
 }
 
 #[test]
@@ -163,7 +583,7 @@ fn test_part1() {
 192: 17 8 14
 21037: 9 7 18 13
 292: 11 6 16 20",
-                   false
+                   PART1_OPERATORS
                )
     );
 }
@@ -181,7 +601,109 @@ fn test_part2() {
 192: 17 8 14
 21037: 9 7 18 13
 292: 11 6 16 20",
-                   true
+                   PART2_OPERATORS
+               )
+    );
+}
+
+#[test]
+fn test_sums_possibly_true_equations() {
+    assert_eq!((3749, 11387),
+               get_sums_possibly_true_equations(
+                   r"190: 10 19
+3267: 81 40 27
+83: 17 5
+156: 15 6
+7290: 6 8 6 15
+161011: 16 10 13
+192: 17 8 14
+21037: 9 7 18 13
+292: 11 6 16 20".lines()
                )
     );
 }
+
+#[test]
+fn test_operators() {
+    assert_eq!(Application::Value(0b1011), Concat { base: 2 }.apply(&0b10u64, &0b11));
+    assert_eq!(Inversion::Solution(0b10), Concat { base: 2 }.invert(&0b1011u64, &0b11));
+    assert_eq!(Inversion::NoSolution, Concat { base: 10 }.invert(&156u64, &7));
+    assert_eq!(Inversion::Solution(15), Concat { base: 10 }.invert(&156u64, &6));
+    assert_eq!(Application::Undefined, Subtract.apply(&3u64, &4));
+    assert_eq!(Inversion::NoSolution, Multiply.invert(&7u64, &2));
+    assert_eq!(Application::Overflow, Concat { base: 10 }.apply(&(u64::MAX / 10), &10));
+
+    let operators = parse_operators::<u64>("-,/,^").unwrap();
+    let operators = operators.iter().map(|operator| operator.as_ref()).collect_vec();
+    // 10 - 4 = 6, 6 / 4 = 1, 1 ^ 3 = 2
+    assert_eq!(Ok(true), is_equation_possibly_true(&parse_equation("2: 10 4 3").unwrap(), &operators));
+    // Can't be reached with + and *, but 9 - 2 = 7
+    assert_eq!(Ok(false), is_equation_possibly_true(&parse_equation("7: 9 2").unwrap(), PART2_OPERATORS));
+    assert_eq!(Ok(true), is_equation_possibly_true(&parse_equation("7: 9 2").unwrap(), &operators));
+    assert!(parse_operators::<u64>("+,%").is_err());
+}
+
+#[test]
+fn test_solutions() {
+    let equation = parse_equation("190: 10 19").unwrap();
+    assert_eq!(
+        "190 = 10 * 19",
+        format_solution(&equation, &find_solution(&equation, PART1_OPERATORS).unwrap().unwrap())
+    );
+
+    let equation = parse_equation("3267: 81 40 27").unwrap();
+    assert_eq!(
+        vec!["3267 = 81 * 40 + 27", "3267 = 81 + 40 * 27"],
+        find_all_solutions(&equation, PART1_OPERATORS).unwrap().iter()
+            .map(|solution| format_solution(&equation, solution))
+            .sorted()
+            .collect_vec()
+    );
+    assert_eq!(Ok(2), count_solutions(&equation, PART1_OPERATORS));
+
+    let equation = parse_equation("7290: 6 8 6 15").unwrap();
+    assert!(find_solution(&equation, PART1_OPERATORS).unwrap().is_none());
+    assert_eq!(
+        "7290 = 6 * 8 || 6 * 15",
+        format_solution(&equation, &find_solution(&equation, PART2_OPERATORS).unwrap().unwrap())
+    );
+
+    // Division can't be undone, so the solver has to search forwards for the terms before it
+    let operators = parse_operators::<u64>("+,/").unwrap();
+    let operators = operators.iter().map(|operator| operator.as_ref()).collect_vec();
+    let equation = parse_equation("3: 7 2 3 2").unwrap();
+    assert_eq!(
+        vec!["3 = 7 / 2 + 3 / 2", "3 = 7 / 2 / 3 + 2"],
+        find_all_solutions(&equation, &operators).unwrap().iter()
+            .map(|solution| format_solution(&equation, solution))
+            .sorted()
+            .collect_vec()
+    );
+}
+
+#[test]
+fn test_big_values() {
+    // Far too big for a u64
+    let input = "123456789012345678901234567890: 123456789012345 678901234567890";
+    assert_eq!(
+        "123456789012345678901234567890".parse::<BigUint>().unwrap(),
+        get_sum_possibly_true_equations(input, BIG_PART2_OPERATORS)
+    );
+
+    // 2^32 * 2^32 overflows a u64 on the way to 2^64 / 2^63 = 2, so the search can't be trusted
+    let operators = parse_operators::<u64>("*,/").unwrap();
+    let operators = operators.iter().map(|operator| operator.as_ref()).collect_vec();
+    let equation = parse_equation::<u64>("2: 4294967296 4294967296 9223372036854775808").unwrap();
+    assert_eq!(Err(Overflow), is_equation_possibly_true(&equation, &operators));
+    assert_eq!(Err(Overflow), count_solutions(&equation, &operators));
+
+    // The last term is bigger than the test value, so nothing can make it true without overflowing
+    let equation = parse_equation::<u64>("5: 1 18446744073709551615").unwrap();
+    assert_eq!(Ok(false), is_equation_possibly_true(&equation, PART2_OPERATORS));
+    assert_eq!(Inversion::NoSolution, Concat { base: 10 }.invert(&5u64, &u64::MAX));
+
+    let operators = parse_operators::<BigUint>("*,/").unwrap();
+    let operators = operators.iter().map(|operator| operator.as_ref()).collect_vec();
+    let equation = parse_equation::<BigUint>("2: 4294967296 4294967296 9223372036854775808").unwrap();
+    assert_eq!(Ok(true), is_equation_possibly_true(&equation, &operators));
+}
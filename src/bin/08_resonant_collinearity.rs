@@ -153,35 +153,207 @@ Calculate the impact of the signal using this updated model. How many
 unique locations within the bounds of the map contain an antinode?
  */
 use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 use itertools::Itertools;
-use std::fs;
+use num::Integer;
+use advent_of_code2024::runner::Runner;
 
 fn main() {
-    let input_file = fs::read_to_string("./inputs/08_resonant_collinearity.txt").unwrap();
-    println!("{}", get_antinodes_count(&input_file, false));
-    println!("{}", get_antinodes_count(&input_file, true));
+    let runner = Runner::from_args("08_resonant_collinearity").solver_version("2");
+    let input_file = runner.read_input();
+    let mode = runner.parsed_option("--mode");
+    let show_frequencies = runner.has_flag("--frequencies");
+    if runner.has_flag("--coordinates") {
+        // The size line says how many dimensions the map has
+        let dimensions = input_file.lines().next()
+            .and_then(|line| line.strip_prefix("size: "))
+            .map_or(0, |size| size.split(',').count());
+        match dimensions {
+            1 => print_antinodes(parse_coordinates::<1>(&input_file).unwrap(), mode, show_frequencies),
+            2 => print_antinodes(parse_coordinates::<2>(&input_file).unwrap(), mode, show_frequencies),
+            3 => print_antinodes(parse_coordinates::<3>(&input_file).unwrap(), mode, show_frequencies),
+            4 => print_antinodes(parse_coordinates::<4>(&input_file).unwrap(), mode, show_frequencies),
+            _ => panic!("Expected a size line with 1 to 4 dimensions, e.g. size: 10,10,10"),
+        }
+        return;
+    }
+    if show_frequencies || mode.is_some() {
+        print_antinodes(preprocess(&input_file), mode, show_frequencies);
+        return;
+    }
+    runner.run_part(1, &input_file, || get_antinodes_count(&input_file, false));
+    runner.run_part(2, &input_file, || get_antinodes_count(&input_file, true));
+}
+
+/// Prints the antinode count for the mode (or both parts' counts without one), or each
+/// frequency's antinodes
+fn print_antinodes<const D: usize>(mut map: Map<D>, mode: Option<Mode>, show_frequencies: bool) {
+    if show_frequencies {
+        for report in get_frequency_reports(&mut map, mode.unwrap_or(Mode::Repeated)) {
+            println!("{report}");
+        }
+    } else if let Some(mode) = mode {
+        println!("{}", count_antinodes(&mut map, mode));
+    } else {
+        println!("{}", count_antinodes(&mut map, Mode::DOUBLED));
+        map.clear_antinodes();
+        println!("{}", count_antinodes(&mut map, Mode::Repeated));
+    }
 }
 
-struct Map {
-    antennas: HashMap<char, Vec<(i64, i64)>>,
+/// Where a pair of antennas of the same frequency make antinodes
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Mode {
+    /// On the line through the antennas where one is `far / near` times as far away as the other,
+    /// beyond each antenna and, if `internal`, between them. Only grid points count.
+    Ratio {
+        near: i64,
+        far: i64,
+        internal: bool,
+    },
+    /// At each antenna and every multiple of the distance between them beyond, stepping by the
+    /// whole distance between them
+    Repeated,
+    /// At every grid point on the line through the antennas, in both directions and between them
+    Lattice,
+}
+
+impl Mode {
+    /// Twice as far from one antenna as the other, beyond them
+    const DOUBLED: Mode = Mode::Ratio { near: 1, far: 2, internal: false };
+}
+
+impl FromStr for Mode {
+    type Err = String;
+
+    /// `doubled`, `repeated`, `lattice` or a ratio such as `1:3`, with `+internal` to include the
+    /// points between the antennas
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "doubled" => Ok(Mode::DOUBLED),
+            "repeated" => Ok(Mode::Repeated),
+            "lattice" => Ok(Mode::Lattice),
+            _ => {
+                let (ratio, internal) = match s.strip_suffix("+internal") {
+                    Some(ratio) => (ratio, true),
+                    None => (s, false),
+                };
+                let (a, b) = ratio.split_once(':')
+                    .and_then(|(a, b)| Some((a.parse::<i64>().ok()?, b.parse::<i64>().ok()?)))
+                    .filter(|&(a, b)| a > 0 && b > 0)
+                    .ok_or_else(|| format!("Unknown mode: {s}"))?;
+                Ok(Mode::Ratio { near: a.min(b), far: a.max(b), internal })
+            },
+        }
+    }
+}
+
+type Position<const D: usize> = [i64; D];
+
+/// Antennas in a box of any number of dimensions, a grid being the 2D case with x then y
+struct Map<const D: usize> {
+    antennas: HashMap<char, Vec<Position<D>>>,
     has_antinode: Vec<bool>,
-    width: usize,
-    height: usize,
+    size: [usize; D],
 }
 
-impl Map {
-    fn in_bounds(&self, x: i64, y: i64) -> bool {
-        x >= 0 && y >= 0 && ((x as usize) < self.width) && ((y as usize) < self.height)
+impl<const D: usize> Map<D> {
+    fn new(antennas: HashMap<char, Vec<Position<D>>>, size: [usize; D]) -> Self {
+        Map {
+            antennas,
+            has_antinode: vec![false; size.iter().product()],
+            size,
+        }
+    }
+
+    fn in_bounds(&self, position: Position<D>) -> bool {
+        position.iter().zip(self.size).all(|(&coordinate, size)| coordinate >= 0 && (coordinate as usize) < size)
+    }
+
+    /// The first coordinate changes fastest, so a grid is in reading order
+    fn index(&self, position: Position<D>) -> usize {
+        position.iter()
+            .zip(self.size)
+            .rev()
+            .fold(0, |index, (&coordinate, size)| index * size + coordinate as usize)
     }
 
-    fn mark_antinode(&mut self, x: i64, y: i64) {
-        if self.in_bounds(x, y) {
-            self.has_antinode[self.width * (y as usize) + (x as usize)] = true;
+    fn position(&self, mut index: usize) -> Position<D> {
+        std::array::from_fn(|dimension| {
+            let coordinate = index % self.size[dimension];
+            index /= self.size[dimension];
+            coordinate as i64
+        })
+    }
+
+    fn mark_antinode(&mut self, position: Position<D>) {
+        if self.in_bounds(position) {
+            let index = self.index(position);
+            self.has_antinode[index] = true;
+        }
+    }
+
+    /// Marks `from + from_to * numerator / denominator` if it's a grid point
+    fn mark_division_point(&mut self, from: Position<D>, from_to: Position<D>, numerator: i64, denominator: i64) {
+        if from_to.iter().all(|offset| (offset * numerator) % denominator == 0) {
+            self.mark_antinode(std::array::from_fn(|d| from[d] + from_to[d] * numerator / denominator));
         }
     }
+
+    /// Marks the antinodes on the side of `to` away from `from` (or between them, nearer `to`),
+    /// the other side is marked when the pair is given the other way round
+    fn mark_pair_antinodes(&mut self, from: Position<D>, to: Position<D>, mode: Mode) {
+        let from_to: Position<D> = std::array::from_fn(|d| to[d] - from[d]);
+        let (mut antinode, step) = match mode {
+            Mode::Ratio { near, far, internal } => {
+                // Equally far from both antennas is only possible between them
+                if near != far {
+                    self.mark_division_point(from, from_to, far, far - near);
+                }
+                if internal {
+                    self.mark_division_point(from, from_to, far, near + far);
+                }
+                return;
+            },
+            Mode::Repeated => (to, from_to),
+            Mode::Lattice => {
+                // The smallest step between grid points on the line
+                let gcd = from_to.iter().fold(0, |gcd, offset| offset.gcd(&gcd));
+                (from, from_to.map(|offset| offset / gcd))
+            },
+        };
+        while self.in_bounds(antinode) {
+            self.mark_antinode(antinode);
+            antinode = std::array::from_fn(|d| antinode[d] + step[d]);
+        }
+    }
+
+    /// Marks the antinodes of every pair of the antennas
+    fn mark_antinodes(&mut self, antennas: &[Position<D>], mode: Mode) {
+        for i in 0..antennas.len() {
+            for j in 0..antennas.len() {
+                if i != j {
+                    self.mark_pair_antinodes(antennas[i], antennas[j], mode);
+                }
+            }
+        }
+    }
+
+    /// The marked antinodes, in reading order for a grid
+    fn antinodes(&self) -> Vec<Position<D>> {
+        self.has_antinode.iter()
+            .positions(|&has_antinode| has_antinode)
+            .map(|i| self.position(i))
+            .collect_vec()
+    }
+
+    fn clear_antinodes(&mut self) {
+        self.has_antinode.fill(false);
+    }
 }
 
-fn preprocess(input: &str) -> Map {
+fn preprocess(input: &str) -> Map<2> {
     let lines = input.lines().collect_vec();
     let height = lines.len();
     let width = lines[0].len(); // char == byte for this problem
@@ -196,46 +368,93 @@ fn preprocess(input: &str) -> Map {
                 if !antennas.contains_key(&c) {
                     antennas.insert(c, vec![]);
                 }
-                antennas.get_mut(&c).unwrap().push((x, y));
+                antennas.get_mut(&c).unwrap().push([x, y]);
             }
         }
     }
 
-    Map {
-        antennas,
-        has_antinode: vec![false; width * height],
-        width,
-        height,
+    Map::new(antennas, [width, height])
+}
+
+fn parse_coordinate_list<T: FromStr, const D: usize>(list: &str) -> Result<[T; D], String> {
+    let coordinates = list.split(',')
+        .map(|coordinate| coordinate.trim().parse::<T>().map_err(|_| format!("Bad coordinate: {coordinate}")))
+        .collect::<Result<Vec<_>, _>>()?;
+    coordinates.try_into().map_err(|_| format!("Expected {D} coordinates: {list}"))
+}
+
+/// Parses a `size: <width>,<height>,...` line followed by a `<frequency>: <x>,<y>,...` line for
+/// each antenna, which can't share its position with another antenna of the same frequency
+fn parse_coordinates<const D: usize>(input: &str) -> Result<Map<D>, String> {
+    let mut lines = input.lines().filter(|line| !line.is_empty());
+    let size = lines.next()
+        .and_then(|line| line.strip_prefix("size: "))
+        .ok_or("Expected a size line")?;
+    let size = parse_coordinate_list(size)?;
+
+    let mut antennas: HashMap<char, Vec<Position<D>>> = HashMap::new();
+    for line in lines {
+        let (frequency, position) = line.split_once(": ").ok_or_else(|| format!("Bad antenna: {line}"))?;
+        let frequency = frequency.chars().exactly_one().map_err(|_| format!("Bad frequency: {frequency}"))?;
+        let position = parse_coordinate_list(position)?;
+        // Two antennas in the same place don't make a line for the antinodes to be on
+        let antennas = antennas.entry(frequency).or_default();
+        if antennas.contains(&position) {
+            return Err(format!("More than one {frequency} antenna at {}", position.iter().join(",")));
+        }
+        antennas.push(position);
     }
+
+    Ok(Map::new(antennas, size))
 }
 
 fn get_antinodes_count(input: &str, repeat: bool) -> u64 {
-    let mut map = preprocess(input);
+    get_antinodes_count_with_mode(input, if repeat { Mode::Repeated } else { Mode::DOUBLED })
+}
+
+fn get_antinodes_count_with_mode(input: &str, mode: Mode) -> u64 {
+    count_antinodes(&mut preprocess(input), mode)
+}
+
+fn count_antinodes<const D: usize>(map: &mut Map<D>, mode: Mode) -> u64 {
     let antennas = map.antennas.clone();
 
     for (_frequency, antennas) in antennas {
-        for i in 0..antennas.len() {
-            for j in 0..antennas.len() {
-                if i != j {
-                    // For all pairs of antennas
-                    let i_to_j = (antennas[j].0 - antennas[i].0, antennas[j].1 - antennas[i].1);
-                    let mut antinode = (antennas[j].0 + i_to_j.0, antennas[j].1 + i_to_j.1);
-                    if repeat {
-                        map.mark_antinode(antennas[j].0, antennas[j].1);
-                    }
-                    while map.in_bounds(antinode.0, antinode.1) {
-                        map.mark_antinode(antinode.0, antinode.1);
-                        antinode = (antinode.0 + i_to_j.0, antinode.1 + i_to_j.1);
-                        if !repeat {
-                            break
-                        }
-                    }
-                }
-            }
+        map.mark_antinodes(&antennas, mode);
+    }
+
+    map.has_antinode.iter().filter(|x| **x).count() as u64
+}
+
+/// The antinodes made by the antennas of one frequency
+#[derive(Debug, Eq, PartialEq)]
+struct FrequencyReport<const D: usize> {
+    frequency: char,
+    antinodes: Vec<Position<D>>,
+}
+
+impl<const D: usize> Display for FrequencyReport<D> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {} antinodes", self.frequency, self.antinodes.len())?;
+        for antinode in &self.antinodes {
+            write!(f, " {}", antinode.iter().join(","))?;
         }
+        Ok(())
     }
+}
+
+/// The antinodes of each frequency, which can overlap with other frequencies' antinodes
+fn get_frequency_reports<const D: usize>(map: &mut Map<D>, mode: Mode) -> Vec<FrequencyReport<D>> {
+    let antennas = map.antennas.clone();
 
-    map.has_antinode.into_iter().filter(|x| *x).count() as u64
+    antennas.into_iter()
+        .sorted()
+        .map(|(frequency, antennas)| {
+            map.clear_antinodes();
+            map.mark_antinodes(&antennas, mode);
+            FrequencyReport { frequency, antinodes: map.antinodes() }
+        })
+        .collect_vec()
 }
 
 #[test]
@@ -279,3 +498,106 @@ fn test_part2() {
                )
     );
 }
+
+#[test]
+fn test_lattice_mode() {
+    // Antennas two columns and four rows apart, with a grid point on the line halfway between them
+    let input = r"a....
+.....
+.....
+.....
+..a..";
+    assert_eq!(2, get_antinodes_count_with_mode(input, Mode::Repeated));
+    assert_eq!(3, get_antinodes_count_with_mode(input, Mode::Lattice));
+
+    // When the antennas are as close as they can be on their line, the two agree
+    let example = r"T.........
+...T......
+.T........
+..........
+..........
+..........
+..........
+..........
+..........
+..........";
+    assert_eq!(9, get_antinodes_count_with_mode(example, Mode::Repeated));
+    assert_eq!(9, get_antinodes_count_with_mode(example, Mode::Lattice));
+}
+
+#[test]
+fn test_frequency_reports() {
+    let input = r"..........
+..........
+..........
+....a.....
+........a.
+.....a....
+..........
+......A...
+..........
+..........";
+    assert_eq!(
+        vec![
+            FrequencyReport { frequency: 'A', antinodes: vec![] },
+            FrequencyReport { frequency: 'a', antinodes: vec![[3, 1], [0, 2], [2, 6], [6, 7]] },
+        ],
+        get_frequency_reports(&mut preprocess(input), Mode::DOUBLED)
+    );
+    assert_eq!(
+        "a: 4 antinodes 3,1 0,2 2,6 6,7",
+        get_frequency_reports(&mut preprocess(input), Mode::DOUBLED)[1].to_string()
+    );
+}
+
+#[test]
+fn test_ratio_modes() {
+    // Antennas at 2 and 6, so 1:3 puts antinodes at 0 and 8, and 4 is halfway between them
+    let input = "size: 10\na: 2\na: 6";
+    let antinodes = |mode: &str| {
+        let mut map = parse_coordinates::<1>(input).unwrap();
+        map.mark_antinodes(&map.antennas[&'a'].clone(), mode.parse().unwrap());
+        map.antinodes()
+    };
+    assert_eq!(vec![[0], [8]], antinodes("1:3"));
+    assert_eq!(vec![[0], [3], [5], [8]], antinodes("3:1+internal"));
+    assert_eq!(vec![[4]], antinodes("1:1+internal"));
+    // Twice as far from one as the other is off the map outside and between grid points inside
+    assert!(antinodes("1:2+internal").is_empty());
+    assert!("0:1".parse::<Mode>().is_err());
+
+    // The part 1 rule still gives the part 1 answer
+    let grid = r"............
+........0...
+.....0......
+.......0....
+....0.......
+......A.....
+............
+............
+........A...
+.........A..
+............
+............";
+    assert_eq!(14, get_antinodes_count_with_mode(grid, "1:2".parse().unwrap()));
+}
+
+#[test]
+fn test_coordinates() {
+    let input = r"size: 5,5,5
+a: 1,1,1
+a: 2,2,2
+b: 0,0,4
+b: 0,2,4";
+    let mut map = parse_coordinates::<3>(input).unwrap();
+    let reports = get_frequency_reports(&mut map, Mode::DOUBLED);
+    assert_eq!(vec![[0, 0, 0], [3, 3, 3]], reports[0].antinodes);
+    assert_eq!(vec![[0, 4, 4]], reports[1].antinodes);
+    assert_eq!("a: 2 antinodes 0,0,0 3,3,3", reports[0].to_string());
+
+    let mut map = parse_coordinates::<3>(input).unwrap();
+    assert_eq!(8, count_antinodes(&mut map, Mode::Repeated));
+    assert!(parse_coordinates::<3>("size: 5,5\na: 1,1,1").is_err());
+    assert!(parse_coordinates::<3>("size: 5,5,5\na: 1,1,1\na: 1,1,1").is_err());
+    assert!(parse_coordinates::<3>("size: 5,5,5\na: 1,1,1\nb: 1,1,1").is_ok());
+}
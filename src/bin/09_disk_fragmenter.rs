@@ -112,14 +112,30 @@ example's checksum would be 2858.
 Start over, now compacting the amphipod's hard drive using this new method
 instead. What is the resulting filesystem checksum?
  */
-use std::cmp::min;
+use std::cmp::{min, Reverse};
+use std::collections::BinaryHeap;
 use itertools::Itertools;
-use std::fs;
+use advent_of_code2024::runner::Runner;
 
 fn main() {
-    let input_file = fs::read_to_string("./inputs/09_disk_fragmenter.txt").unwrap();
-    println!("{}", get_compacted_checksum(&input_file));
-    println!("{}", get_compacted_checksum_no_fragmentation(&input_file));
+    let runner = Runner::from_args("09_disk_fragmenter").solver_version("2");
+    let input_file = runner.read_input();
+    if runner.has_flag("--layout") || runner.option("--layout").is_some() {
+        let layouts = [get_compacted_layout(&input_file), get_compacted_layout_no_fragmentation(&input_file)];
+        if runner.option("--layout") == Some("csv") {
+            println!("part,position,size,file_id");
+            for (part, layout) in layouts.iter().enumerate() {
+                print!("{}", layout.to_csv(part + 1));
+            }
+        } else {
+            for layout in layouts {
+                println!("{}", layout.render());
+            }
+        }
+        return;
+    }
+    runner.run_part(1, &input_file, || get_compacted_checksum(&input_file));
+    runner.run_part(2, &input_file, || get_compacted_checksum_no_fragmentation(&input_file));
 }
 
 #[derive(Debug)]
@@ -140,7 +156,90 @@ fn arithmetic_series(n: u64) -> u64 {
     n * n.saturating_sub(1) / 2
 }
 
+/// A file's blocks, or some of them, next to each other on the disk
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+struct Run {
+    position: u64,
+    size: u64,
+    file_id: u64,
+}
+
+/// Where each file's blocks are after compacting, as runs in position order with free space
+/// between them
+#[derive(Debug, Default, Eq, PartialEq)]
+struct Layout {
+    runs: Vec<Run>,
+}
+
+impl Layout {
+    fn from_runs(runs: impl IntoIterator<Item = Run>) -> Self {
+        let mut layout = Layout::default();
+        for run in runs.into_iter().sorted_by_key(|run| run.position) {
+            layout.push(run);
+        }
+        layout
+    }
+
+    /// Adds a run after the others, joining it onto the last one if it continues the same file
+    fn push(&mut self, run: Run) {
+        if run.size == 0 {
+            return;
+        }
+        match self.runs.last_mut() {
+            Some(last) if last.file_id == run.file_id && last.position + last.size == run.position => {
+                last.size += run.size;
+            },
+            _ => self.runs.push(run),
+        }
+    }
+
+    fn checksum(&self) -> u64 {
+        self.runs.iter()
+            .map(|run| run.file_id * (arithmetic_series(run.position + run.size) - arithmetic_series(run.position)))
+            .sum()
+    }
+
+    /// The runs with the free space between them, in order
+    fn runs_and_gaps(&self) -> impl Iterator<Item = (Option<u64>, u64)> + '_ {
+        let mut position = 0;
+        self.runs.iter()
+            .flat_map(move |run| {
+                let gap = run.position - position;
+                position = run.position + run.size;
+                [(None, gap), (Some(run.file_id), run.size)]
+            })
+            .filter(|&(_, size)| size > 0)
+    }
+
+    /// A digit per block with `.` for free blocks, e.g. `0099811188827773336446555566`, as long as
+    /// every file ID is a single digit. Otherwise each run is written once with its size, e.g.
+    /// `0*2 .*3 10*3 .*3 2`.
+    fn render(&self) -> String {
+        let symbol = |file_id: Option<u64>| file_id.map_or(".".to_string(), |file_id| file_id.to_string());
+        if self.runs.iter().all(|run| run.file_id < 10) {
+            self.runs_and_gaps()
+                .map(|(file_id, size)| symbol(file_id).repeat(size as usize))
+                .collect()
+        } else {
+            self.runs_and_gaps()
+                .map(|(file_id, size)| if size == 1 { symbol(file_id) } else { format!("{}*{size}", symbol(file_id)) })
+                .join(" ")
+        }
+    }
+
+    /// A `part,position,size,file_id` line for each run
+    fn to_csv(&self, part: usize) -> String {
+        self.runs.iter()
+            .map(|run| format!("{part},{},{},{}\n", run.position, run.size, run.file_id))
+            .collect()
+    }
+}
+
 fn get_compacted_checksum(input: &str) -> u64 {
+    get_compacted_layout(input).checksum()
+}
+
+fn get_compacted_layout(input: &str) -> Layout {
     let mut input_blocks = input.char_indices()
         .map(|(i, c)| Block {
             size: c.to_digit(10).unwrap() as u64,
@@ -154,7 +253,7 @@ fn get_compacted_checksum(input: &str) -> u64 {
         })
         .collect_vec();
 
-    let mut result = 0u64;
+    let mut layout = Layout::default();
     let mut index = 0u64;
 
     let mut block_view = &mut input_blocks[..];
@@ -162,9 +261,8 @@ fn get_compacted_checksum(input: &str) -> u64 {
     while !block_view.is_empty() {
         match block_view[0].block_type {
             Type::Occupied { file_id } => {
-                // The value of the first file
-                let value = file_id * (arithmetic_series(index + block_view[0].size) - arithmetic_series(index));
-                result += value;
+                // The first file stays where it is
+                layout.push(Run { position: index, size: block_view[0].size, file_id });
                 index += block_view[0].size;
 
                 // Remove it from our view
@@ -180,8 +278,7 @@ fn get_compacted_checksum(input: &str) -> u64 {
                     Type::Occupied { file_id } => {
                         // We can only move as many blocks as the smallest of the two
                         let size = min(block_view[0].size, block_view[block_view.len() - 1].size);
-                        let value = file_id * (arithmetic_series(index + size) - arithmetic_series(index));
-                        result += value;
+                        layout.push(Run { position: index, size, file_id });
                         index += size;
 
                         if size >= block_view[0].size {
@@ -201,7 +298,7 @@ fn get_compacted_checksum(input: &str) -> u64 {
         }
     }
 
-    result
+    layout
 }
 
 
@@ -214,15 +311,17 @@ struct Occupied {
     file_id: u64,
 }
 
-#[derive(Debug)]
-struct Free {
-    size: u64,
-    position: u64,
-}
+/// The largest a free span can be, as each is given by a single digit
+const MAX_SPAN_SIZE: usize = 9;
 
 fn get_compacted_checksum_no_fragmentation(input: &str) -> u64 {
+    get_compacted_layout_no_fragmentation(input).checksum()
+}
+
+fn get_compacted_layout_no_fragmentation(input: &str) -> Layout {
     let mut occupied = vec![];
-    let mut free = vec![];
+    // The positions of the free spans of each size, leftmost first
+    let mut free: [BinaryHeap<Reverse<u64>>; MAX_SPAN_SIZE + 1] = Default::default();
 
     let mut position = 0u64;
     input.char_indices()
@@ -234,43 +333,40 @@ fn get_compacted_checksum_no_fragmentation(input: &str) -> u64 {
                     position,
                     file_id: (i / 2) as u64,
                 });
-            } else {
-                free.push(Free {
-                    size,
-                    position,
-                });
+            } else if size > 0 {
+                free[size as usize].push(Reverse(position));
             }
             position += size;
         });
 
     for occupied in occupied.iter_mut().rev() {
-        for free in &mut free {
-            // No sense in searching in locations that would move the files to the right
-            if free.position >= occupied.position {
-                break;
-            }
-            // If the occupied would fit in here
-            if free.size >= occupied.size {
-                // Move the occupied to here
-                occupied.position = free.position;
-                // Shrink the size of the free
-                free.position += occupied.size;
-                free.size -= occupied.size;
-
-                // Originally I considered removing a segment from free if it had shrunk to zero
-                // size. However, I don't think that would actually speed anything up since removing
-                // is not free
-            }
+        // The leftmost of the spans big enough to hold the file
+        let Some((span_size, Reverse(span_position))) = (occupied.size as usize..=MAX_SPAN_SIZE)
+            .filter_map(|span_size| free[span_size].peek().map(|&position| (span_size, position)))
+            .max_by_key(|&(_, position)| position) else {
+            continue;
+        };
+        // No sense in moving the file to the right
+        if span_position >= occupied.position {
+            continue;
+        }
+        free[span_size].pop();
+
+        // Move the occupied to here, and put back what's left of the free
+        occupied.position = span_position;
+        let remaining_size = span_size - occupied.size as usize;
+        if remaining_size > 0 {
+            free[remaining_size].push(Reverse(span_position + occupied.size));
         }
+        // The space the file leaves behind is never used, as only files to the left of it are left
+        // to move and they only move further left
     }
 
-    occupied.into_iter()
-        .map(|occupied|
-            occupied.file_id * (
-                arithmetic_series(occupied.position + occupied.size) - arithmetic_series(occupied.position)
-            )
-        )
-        .sum()
+    Layout::from_runs(occupied.into_iter().map(|occupied| Run {
+        position: occupied.position,
+        size: occupied.size,
+        file_id: occupied.file_id,
+    }))
 }
 
 #[test]
@@ -290,3 +386,53 @@ fn test_part2() {
                )
     );
 }
+
+#[test]
+fn test_no_fragmentation_matches_block_by_block() {
+    // Moves whole files a block at a time, leftmost span first, to check against
+    fn simulate(input: &str) -> u64 {
+        let mut blocks = input.char_indices()
+            .flat_map(|(i, c)| {
+                let file_id = (i % 2 == 0).then_some((i / 2) as u64);
+                std::iter::repeat_n(file_id, c.to_digit(10).unwrap() as usize)
+            })
+            .collect_vec();
+        let file_count = input.len().div_ceil(2) as u64;
+        for file_id in (0..file_count).rev() {
+            let start = blocks.iter().position(|&block| block == Some(file_id)).unwrap();
+            let size = blocks.iter().filter(|&&block| block == Some(file_id)).count();
+            let span = (0..start).find(|&span| blocks[span..span + size].iter().all(|block| block.is_none()));
+            if let Some(span) = span {
+                blocks[span..span + size].fill(Some(file_id));
+                blocks[start..start + size].fill(None);
+            }
+        }
+        blocks.iter()
+            .enumerate()
+            .map(|(position, block)| position as u64 * block.unwrap_or(0))
+            .sum()
+    }
+
+    // A deterministic jumble of sizes, including empty spans and files that don't fit anywhere
+    let mut state = 12345u64;
+    let input = (0..401)
+        .map(|i| {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            let digit = (state >> 33) % 10;
+            char::from_digit(if i % 2 == 0 { digit.max(1) } else { digit } as u32, 10).unwrap()
+        })
+        .collect::<String>();
+    assert_eq!(simulate(&input), get_compacted_checksum_no_fragmentation(&input));
+}
+
+#[test]
+fn test_layouts() {
+    assert_eq!("0099811188827773336446555566", get_compacted_layout("2333133121414131402").render());
+    assert_eq!("00992111777.44.333....5555.6666.....8888", get_compacted_layout_no_fragmentation("2333133121414131402").render());
+
+    // File 12 fills all but the last gap, then one block of file 11 moves next to the rest of it
+    let layout = get_compacted_layout("1111111111111111111110309");
+    assert_eq!("0 12 1 12 2 12 3 12 4 12 5 12 6 12 7 12 8 12 9 11 10 11*2", layout.render());
+    assert_eq!(get_compacted_checksum("1111111111111111111110309"), layout.checksum());
+    assert_eq!(Some("1,21,2,11"), layout.to_csv(1).lines().last());
+}
@@ -162,13 +162,14 @@ out of toothpicks and bits of paper and is using them to mark trailheads on
 your topographic map. What is the sum of the ratings of all trailheads?
  */
 use std::collections::VecDeque;
-use std::fs;
+use advent_of_code2024::runner::Runner;
 use itertools::Itertools;
 
 fn main() {
-    let input_file = fs::read_to_string("./inputs/10_hoof_it.txt").unwrap();
-    println!("{}", get_sum_trailhead_scores(&input_file));
-    println!("{}", get_sum_trailhead_ratings(&input_file));
+    let runner = Runner::from_args("10_hoof_it");
+    let input_file = runner.read_input();
+    runner.run_part(1, &input_file, || get_sum_trailhead_scores(&input_file));
+    runner.run_part(2, &input_file, || get_sum_trailhead_ratings(&input_file));
 }
 
 #[derive(Debug)]
@@ -84,12 +84,13 @@ How many stones would you have after blinking a total of 75 times?
  */
 use itertools::Itertools;
 use std::collections::HashMap;
-use std::fs;
+use advent_of_code2024::runner::Runner;
 
 fn main() {
-    let input_file = fs::read_to_string("./inputs/11_plutonian_pebbles.txt").unwrap();
-    println!("{}", get_num_stones_after_blinks(&input_file, 25));
-    println!("{}", get_num_stones_after_blinks(&input_file, 75));
+    let runner = Runner::from_args("11_plutonian_pebbles");
+    let input_file = runner.read_input();
+    runner.run_part(1, &input_file, || get_num_stones_after_blinks(&input_file, 25));
+    runner.run_part(2, &input_file, || get_num_stones_after_blinks(&input_file, 75));
 }
 
 fn insert_stones(stones: &mut HashMap<u64, usize>, number: u64, count: usize) {
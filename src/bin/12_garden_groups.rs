@@ -194,13 +194,20 @@ Adding these together produces its new total price of 1206.
 
 What is the new total price of fencing all regions on your map?
  */
-use std::fs;
+use advent_of_code2024::image::{distinct_colour, save_grid_ppm};
+use advent_of_code2024::runner::Runner;
+use advent_of_code2024::visualise::Grid;
 use itertools::Itertools;
 
 fn main() {
-    let input_file = fs::read_to_string("./inputs/12_garden_groups.txt").unwrap();
-    println!("{}", get_total_price(&input_file));
-    println!("{}", get_total_price_bulk(&input_file));
+    let runner = Runner::from_args("12_garden_groups");
+    let input_file = runner.read_input();
+    if let Some(path) = runner.option("--export") {
+        export_regions(&input_file, path, runner.export_scale()).unwrap();
+        return;
+    }
+    runner.run_part(1, &input_file, || get_total_price(&input_file));
+    runner.run_part(2, &input_file, || get_total_price_bulk(&input_file));
 }
 
 #[derive(Debug, Default)]
@@ -345,6 +352,29 @@ fn get_total_price_bulk(input: &str) -> u64 {
     map.region_info.into_iter().map(|region| region.sides * region.area).sum()
 }
 
+impl Grid for Map {
+    type Tile = usize;
+
+    fn width(&self) -> usize {
+        self.component_map[0].len()
+    }
+
+    fn height(&self) -> usize {
+        self.component_map.len()
+    }
+
+    fn tile(&self, x: usize, y: usize) -> usize {
+        self.component_map[y][x]
+    }
+}
+
+/// Saves the map as an image with each region in its own colour
+fn export_regions(input: &str, path: &str, scale: usize) -> std::io::Result<()> {
+    let mut map = preprocess(input);
+    evaluate_map_regions(&mut map);
+    save_grid_ppm(path, &map, scale, distinct_colour)
+}
+
 #[test]
 fn test_part1() {
     assert_eq!(140,
@@ -109,13 +109,14 @@ Using the corrected prize coordinates, figure out how to win as many prizes
 as possible. What is the fewest tokens you would have to spend to win all
 possible prizes?
  */
-use std::fs;
+use advent_of_code2024::runner::Runner;
 use regex::Regex;
 
 fn main() {
-    let input_file = fs::read_to_string("./inputs/13_claw_contraption.txt").unwrap();
-    println!("{}", get_minimum_tokens_to_win(&input_file, 0));
-    println!("{}", get_minimum_tokens_to_win(&input_file, 10000000000000));
+    let runner = Runner::from_args("13_claw_contraption");
+    let input_file = runner.read_input();
+    runner.run_part(1, &input_file, || get_minimum_tokens_to_win(&input_file, 0));
+    runner.run_part(2, &input_file, || get_minimum_tokens_to_win(&input_file, 10000000000000));
 }
 
 #[derive(Debug, Default)]
@@ -159,13 +159,29 @@ display the Easter egg?
  */
 use itertools::Itertools;
 use regex::Regex;
-use std::fs;
+use advent_of_code2024::image::{save_grid_pgm, sequence_path};
+use advent_of_code2024::runner::Runner;
+use advent_of_code2024::visualise::Grid;
 use std::thread::sleep;
 use std::time::Duration;
 
 fn main() {
-    let input_file = fs::read_to_string("./inputs/14_restroom_redoubt.txt").unwrap();
-    println!("{}", get_safety_factor(&input_file, 101, 103));
+    let runner = Runner::from_args("14_restroom_redoubt");
+    let input_file = runner.read_input();
+    if let Some(path) = runner.option("--export") {
+        let seconds = runner.parsed_option("--seconds").unwrap_or(6446);
+        export_robots(&input_file, seconds, 101, 103, path, runner.export_scale()).unwrap();
+        return;
+    }
+    if let Some(prefix) = runner.option("--export-sequence") {
+        let start = runner.parsed_option("--seconds").unwrap_or(0);
+        let frames = runner.parsed_option("--frames").unwrap_or(103);
+        for (index, seconds) in (start..start + frames).enumerate() {
+            export_robots(&input_file, seconds, 101, 103, &sequence_path(prefix, index, "pgm"), runner.export_scale()).unwrap();
+        }
+        return;
+    }
+    runner.run_part(1, &input_file, || get_safety_factor(&input_file, 101, 103));
     // This value of 6446 was found by manual verification.
     // Since the two dimensions are 101 and 103, vertical motion is modulo 103 and horizontal motion
     // is modulo 101
@@ -176,12 +192,14 @@ fn main() {
     // tell because there was clearly a tree structure).
     display_robots(&input_file, 6446, 101, 103);
 
-    let mut seconds = 60;
-    for _ in 0..101 {
-        println!("{seconds}");
-        display_robots(&input_file, seconds, 101, 103);
-        seconds += 103;
-        sleep(Duration::from_millis(2000));
+    if runner.has_flag("--search") {
+        let mut seconds = 60;
+        for _ in 0..101 {
+            println!("{seconds}");
+            display_robots(&input_file, seconds, 101, 103);
+            seconds += 103;
+            sleep(Duration::from_millis(2000));
+        }
     }
 }
 
@@ -252,7 +270,28 @@ fn get_safety_factor(input: &str, width: u64, height: u64) -> u64 {
     quadrants.into_iter().product()
 }
 
-fn display_robots(input: &str, seconds: i64, width: u64, height: u64) {
+/// How many robots are on each tile
+struct RobotCounts {
+    tiles: Vec<Vec<u64>>,
+}
+
+impl Grid for RobotCounts {
+    type Tile = u64;
+
+    fn width(&self) -> usize {
+        self.tiles[0].len()
+    }
+
+    fn height(&self) -> usize {
+        self.tiles.len()
+    }
+
+    fn tile(&self, x: usize, y: usize) -> u64 {
+        self.tiles[y][x]
+    }
+}
+
+fn get_robot_counts(input: &str, seconds: i64, width: u64, height: u64) -> RobotCounts {
     let mut robots = preprocess(input);
     robots.iter_mut().for_each(|robot| robot.step(seconds, width, height));
 
@@ -261,7 +300,11 @@ fn display_robots(input: &str, seconds: i64, width: u64, height: u64) {
         tiles[robot.pos.y as usize][robot.pos.x as usize] += 1;
     }
 
-    for row in tiles {
+    RobotCounts { tiles }
+}
+
+fn display_robots(input: &str, seconds: i64, width: u64, height: u64) {
+    for row in get_robot_counts(input, seconds, width, height).tiles {
         for tile in row {
             if tile >= 10 {
                 print!("X");
@@ -275,6 +318,12 @@ fn display_robots(input: &str, seconds: i64, width: u64, height: u64) {
     }
 }
 
+/// Saves the robot positions as a greyscale image, any tile with a robot on it is white
+fn export_robots(input: &str, seconds: i64, width: u64, height: u64, path: &str, scale: usize) -> std::io::Result<()> {
+    let robot_counts = get_robot_counts(input, seconds, width, height);
+    save_grid_pgm(path, &robot_counts, scale, |count| if count > 0 { 255 } else { 0 })
+}
+
 #[test]
 fn test_part1() {
     assert_eq!(12,
@@ -465,13 +465,22 @@ What is the sum of all boxes' final GPS coordinates?
  */
 use std::cmp::max;
 use std::fmt::{Display, Formatter};
-use std::fs;
+use advent_of_code2024::runner::Runner;
+use advent_of_code2024::visualise::{Colour, Grid, Legend, Renderer};
 use itertools::Itertools;
 
 fn main() {
-    let input_file = fs::read_to_string("./inputs/15_warehouse_woes.txt").unwrap();
-    println!("{}", get_final_box_gps_sum(&input_file));
-    println!("{}", get_final_box_gps_sum_double(&input_file));
+    let runner = Runner::from_args("15_warehouse_woes");
+    let input_file = runner.read_input();
+    if runner.has_flag("--visualise") {
+        let (map, instructions) = preprocess(&input_file);
+        visualise_box_pushing(map, &instructions, apply_instruction, &mut runner.renderer(DisplayTile::legend()));
+        let (map, instructions) = preprocess_double(&input_file);
+        visualise_box_pushing(map, &instructions, apply_instruction_double, &mut runner.renderer(DisplayTile::legend_double()));
+        return;
+    }
+    runner.run_part(1, &input_file, || get_final_box_gps_sum(&input_file));
+    runner.run_part(2, &input_file, || get_final_box_gps_sum_double(&input_file));
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Default)]
@@ -795,6 +804,67 @@ fn apply_instruction_double(map: &mut Map<TileDouble>, instruction: Instruction)
     }
 }
 
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum DisplayTile<T> {
+    Robot,
+    Tile(T),
+}
+
+impl DisplayTile<Tile> {
+    fn legend() -> Legend<Self> {
+        Legend::new()
+            .with(DisplayTile::Robot, '@', Colour::Red, "robot")
+            .with(DisplayTile::Tile(Tile::Wall), '#', Colour::White, "wall")
+            .with(DisplayTile::Tile(Tile::Empty), '.', Colour::BrightBlack, "empty")
+            .with(DisplayTile::Tile(Tile::Box), 'O', Colour::Yellow, "box")
+    }
+}
+
+impl DisplayTile<TileDouble> {
+    fn legend_double() -> Legend<Self> {
+        Legend::new()
+            .with(DisplayTile::Robot, '@', Colour::Red, "robot")
+            .with(DisplayTile::Tile(TileDouble::Wall), '#', Colour::White, "wall")
+            .with(DisplayTile::Tile(TileDouble::Empty), '.', Colour::BrightBlack, "empty")
+            .with(DisplayTile::Tile(TileDouble::BoxLeft), '[', Colour::Yellow, "box")
+            .with(DisplayTile::Tile(TileDouble::BoxRight), ']', Colour::Yellow, "")
+    }
+}
+
+impl<T: Display + Copy + Default + Clone + PartialEq> Grid for Map<T> {
+    type Tile = DisplayTile<T>;
+
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn tile(&self, x: usize, y: usize) -> DisplayTile<T> {
+        if x as i64 == self.robot.0 && y as i64 == self.robot.1 {
+            DisplayTile::Robot
+        } else {
+            DisplayTile::Tile(self.get(x as i64, y as i64))
+        }
+    }
+}
+
+fn visualise_box_pushing<T: Display + Copy + Default + Clone + PartialEq>(
+    mut map: Map<T>,
+    instructions: &[Instruction],
+    apply_instruction: fn(&mut Map<T>, Instruction),
+    renderer: &mut Renderer<DisplayTile<T>>
+) {
+    renderer.frame(&map);
+    for instruction in instructions {
+        apply_instruction(&mut map, *instruction);
+        renderer.frame(&map);
+    }
+    renderer.final_frame(&map);
+}
+
 fn get_final_box_gps_sum(input: &str) -> u64 {
     let (mut map, instructions) = preprocess(input);
     for instruction in instructions {
@@ -161,12 +161,23 @@ best paths through the maze?
  */
 use std::cmp::{max, min, Ordering, PartialEq, Reverse};
 use std::collections::{BinaryHeap, HashSet};
-use std::fs;
+use advent_of_code2024::image::{save_grid_ppm, Rgb};
+use advent_of_code2024::runner::Runner;
+use advent_of_code2024::visualise::{Colour, Grid, Legend, Renderer};
 
 fn main() {
-    let input_file = fs::read_to_string("./inputs/16_reindeer_maze.txt").unwrap();
-    println!("{}", get_lowest_score(&input_file));
-    println!("{}", get_best_paths_tile_count(&input_file));
+    let runner = Runner::from_args("16_reindeer_maze");
+    let input_file = runner.read_input();
+    if runner.has_flag("--visualise") {
+        visualise_dijkstra(&input_file, &mut runner.renderer(DisplayTile::legend()));
+        return;
+    }
+    if let Some(path) = runner.option("--export") {
+        export_best_paths(&input_file, path, runner.export_scale()).unwrap();
+        return;
+    }
+    runner.run_part(1, &input_file, || get_lowest_score(&input_file));
+    runner.run_part(2, &input_file, || get_best_paths_tile_count(&input_file));
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
@@ -401,6 +412,15 @@ fn preprocess(input: &str) -> (Map, (i64, i64)) {
 }
 
 fn dijkstra(map: &mut Map, start: (i64, i64)) -> (usize, (i64, i64)) {
+    dijkstra_observed(map, start, |_, _| {})
+}
+
+/// Dijkstra's algorithm, calling `on_settle` with the frontier each time a state's cost is settled
+fn dijkstra_observed(
+    map: &mut Map,
+    start: (i64, i64),
+    mut on_settle: impl FnMut(&Map, &BinaryHeap<Reverse<Entry>>)
+) -> (usize, (i64, i64)) {
     let mut heap = BinaryHeap::new();
     heap.push(Reverse(Entry {
         state: State {
@@ -437,11 +457,82 @@ fn dijkstra(map: &mut Map, start: (i64, i64)) -> (usize, (i64, i64)) {
                 }));
             }
         }
+
+        on_settle(map, &heap);
     }
 
     (win_cost, win_pos)
 }
 
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum DisplayTile {
+    Empty,
+    Wall,
+    End,
+    Settled,
+    Frontier,
+}
+
+impl DisplayTile {
+    fn legend() -> Legend<Self> {
+        Legend::new()
+            .with(DisplayTile::Empty, '.', Colour::BrightBlack, "empty")
+            .with(DisplayTile::Wall, '#', Colour::White, "wall")
+            .with(DisplayTile::End, 'E', Colour::Red, "end")
+            .with(DisplayTile::Settled, 'o', Colour::Blue, "settled")
+            .with(DisplayTile::Frontier, '*', Colour::Yellow, "frontier")
+    }
+}
+
+/// A snapshot of Dijkstra's algorithm part way through the search
+struct FrontierView<'a> {
+    map: &'a Map,
+    frontier: HashSet<(i64, i64)>,
+}
+
+impl Grid for FrontierView<'_> {
+    type Tile = DisplayTile;
+
+    fn width(&self) -> usize {
+        self.map.width
+    }
+
+    fn height(&self) -> usize {
+        self.map.height
+    }
+
+    fn tile(&self, x: usize, y: usize) -> DisplayTile {
+        let (x, y) = (x as i64, y as i64);
+        match self.map.get(x, y) {
+            Tile::Wall => DisplayTile::Wall,
+            Tile::End => DisplayTile::End,
+            Tile::Empty if self.frontier.contains(&(x, y)) => DisplayTile::Frontier,
+            Tile::Empty => {
+                let is_settled = [Direction::Up, Direction::Right, Direction::Down, Direction::Left]
+                    .into_iter()
+                    .any(|direction| self.map.get_distance(&State { x, y, direction }) != usize::MAX);
+                if is_settled {
+                    DisplayTile::Settled
+                } else {
+                    DisplayTile::Empty
+                }
+            },
+        }
+    }
+}
+
+fn visualise_dijkstra(input: &str, renderer: &mut Renderer<DisplayTile>) {
+    let (mut map, start) = preprocess(input);
+    dijkstra_observed(&mut map, start, |map, heap| {
+        renderer.frame_with(|| FrontierView {
+            map,
+            frontier: heap.iter().map(|Reverse(entry)| (entry.state.x, entry.state.y)).collect(),
+        })
+    });
+    // Once the search is over, everything it settled
+    renderer.final_frame(&FrontierView { map: &map, frontier: HashSet::new() });
+}
+
 fn get_lowest_score(input: &str) -> u64 {
     let (mut map, start) = preprocess(input);
     dijkstra(&mut map, start).0 as u64
@@ -480,7 +571,8 @@ fn dfs_find_all_paths(map: &Map, visited_positions: &mut HashSet<(i64, i64)>, st
     is_in_path
 }
 
-fn get_best_paths_tile_count(input: &str) -> u64 {
+/// The tiles which are part of at least one of the best paths
+fn get_best_paths_tiles(input: &str) -> (Map, HashSet<(i64, i64)>) {
     let (mut map, start) = preprocess(input);
     let (win_cost, win_pos) = dijkstra(&mut map, start);
 
@@ -515,7 +607,63 @@ fn get_best_paths_tile_count(input: &str) -> u64 {
         direction: Direction::Left,
     }, win_cost, &start);
 
-    visited_positions.len() as u64
+    (map, visited_positions)
+}
+
+fn get_best_paths_tile_count(input: &str) -> u64 {
+    get_best_paths_tiles(input).1.len() as u64
+}
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum PathTile {
+    Empty,
+    Wall,
+    End,
+    BestPath,
+}
+
+impl PathTile {
+    fn colour(self) -> Rgb {
+        match self {
+            PathTile::Empty => [255, 255, 255],
+            PathTile::Wall => [64, 64, 64],
+            PathTile::End => [255, 0, 0],
+            PathTile::BestPath => [0, 160, 255],
+        }
+    }
+}
+
+struct BestPathsView<'a> {
+    map: &'a Map,
+    best_paths_tiles: &'a HashSet<(i64, i64)>,
+}
+
+impl Grid for BestPathsView<'_> {
+    type Tile = PathTile;
+
+    fn width(&self) -> usize {
+        self.map.width
+    }
+
+    fn height(&self) -> usize {
+        self.map.height
+    }
+
+    fn tile(&self, x: usize, y: usize) -> PathTile {
+        let (x, y) = (x as i64, y as i64);
+        match self.map.get(x, y) {
+            Tile::Wall => PathTile::Wall,
+            Tile::End => PathTile::End,
+            Tile::Empty if self.best_paths_tiles.contains(&(x, y)) => PathTile::BestPath,
+            Tile::Empty => PathTile::Empty,
+        }
+    }
+}
+
+/// Saves the maze as an image with the tiles on any of the best paths highlighted
+fn export_best_paths(input: &str, path: &str, scale: usize) -> std::io::Result<()> {
+    let (map, best_paths_tiles) = get_best_paths_tiles(input);
+    save_grid_ppm(path, &BestPathsView { map: &map, best_paths_tiles: &best_paths_tiles }, scale, PathTile::colour)
 }
 
 #[test]
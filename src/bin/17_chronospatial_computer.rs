@@ -133,13 +133,14 @@ program to output a copy of itself?
 use itertools::Itertools;
 use std::cmp::PartialEq;
 use std::fmt::{Display, Formatter};
-use std::fs;
+use advent_of_code2024::runner::Runner;
 use std::ops::{BitXor, Shr};
 
 fn main() {
-    let input_file = fs::read_to_string("./inputs/17_chronospatial_computer.txt").unwrap();
-    println!("{}", get_output_string(&input_file));
-    println!("{}", get_reg_a_value(&input_file));
+    let runner = Runner::from_args("17_chronospatial_computer");
+    let input_file = runner.read_input();
+    runner.run_part(1, &input_file, || get_output_string(&input_file));
+    runner.run_part(2, &input_file, || get_reg_a_value(&input_file));
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
@@ -129,14 +129,22 @@ integers separated by a comma with no other characters.)
  */
 use std::cmp::{Ordering, Reverse};
 use std::collections::BinaryHeap;
-use std::fs;
+use advent_of_code2024::runner::Runner;
+use advent_of_code2024::visualise::{Colour, Grid, Legend, Renderer};
 use itertools::Itertools;
 
 fn main() {
-    let input_file = fs::read_to_string("./inputs/18_ram_run.txt").unwrap();
-    println!("{}", get_minimum_steps_to_exit(&input_file, 71, 1024));
-    let coordinates_cutoff = get_coordinates_cutoff(&input_file, 71);
-    println!("{},{}", coordinates_cutoff.0, coordinates_cutoff.1);
+    let runner = Runner::from_args("18_ram_run");
+    let input_file = runner.read_input();
+    if runner.has_flag("--visualise") {
+        visualise_byte_falls(&input_file, 71, &mut runner.renderer(DisplayTile::legend()));
+        return;
+    }
+    runner.run_part(1, &input_file, || get_minimum_steps_to_exit(&input_file, 71, 1024));
+    runner.run_part(2, &input_file, || {
+        let coordinates_cutoff = get_coordinates_cutoff(&input_file, 71);
+        format!("{},{}", coordinates_cutoff.0, coordinates_cutoff.1)
+    });
 }
 
 struct Map {
@@ -314,6 +322,74 @@ fn get_coordinates_cutoff(input: &str, size: usize) -> (i64, i64) {
     (x.parse::<i64>().unwrap(), y.parse::<i64>().unwrap())
 }
 
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum DisplayTile {
+    Safe,
+    Corrupted,
+    LatestByte,
+    Explored,
+}
+
+impl DisplayTile {
+    fn legend() -> Legend<Self> {
+        Legend::new()
+            .with(DisplayTile::Safe, '.', Colour::BrightBlack, "safe")
+            .with(DisplayTile::Corrupted, '#', Colour::White, "corrupted")
+            .with(DisplayTile::LatestByte, '#', Colour::Red, "latest byte")
+            .with(DisplayTile::Explored, 'O', Colour::Green, "explored")
+    }
+}
+
+/// The memory space just after a byte has fallen
+struct ByteFallView<'a> {
+    map: &'a Map,
+    latest_byte: (i64, i64),
+}
+
+impl Grid for ByteFallView<'_> {
+    type Tile = DisplayTile;
+
+    fn width(&self) -> usize {
+        self.map.width
+    }
+
+    fn height(&self) -> usize {
+        self.map.height
+    }
+
+    fn tile(&self, x: usize, y: usize) -> DisplayTile {
+        let (x, y) = (x as i64, y as i64);
+        if (x, y) == self.latest_byte {
+            DisplayTile::LatestByte
+        } else if self.map.is_corrupted(x, y) {
+            DisplayTile::Corrupted
+        } else if self.map.is_visited(x, y) {
+            DisplayTile::Explored
+        } else {
+            DisplayTile::Safe
+        }
+    }
+}
+
+/// Drops the bytes one at a time, showing how far the search for the exit gets after each one,
+/// until the exit is cut off
+fn visualise_byte_falls(input: &str, size: usize, renderer: &mut Renderer<DisplayTile>) {
+    let mut map = Map::new(size);
+    for line in input.lines() {
+        let Some((left, right)) = line.split_once(',') else { panic!("Bad Format") };
+        let latest_byte = (left.parse::<i64>().unwrap(), right.parse::<i64>().unwrap());
+        map.set_corrupted(latest_byte.0, latest_byte.1, true);
+
+        map.visited.fill(false);
+        let exit = astar(&mut map, (0, 0), (size as i64 - 1, size as i64 - 1));
+        if exit.is_none() {
+            renderer.final_frame(&ByteFallView { map: &map, latest_byte });
+            break;
+        }
+        renderer.frame(&ByteFallView { map: &map, latest_byte });
+    }
+}
+
 #[test]
 fn test_part1() {
     assert_eq!(22,
@@ -113,14 +113,15 @@ the desired designs yields 16 (2 + 1 + 4 + 6 + 1 + 2).
 They'll let you into the onsen as soon as you have the list. What do you
 get if you add up the number of different ways you could make each design?
  */
-use std::fs;
+use advent_of_code2024::runner::Runner;
 use itertools::Itertools;
 use regex::Regex;
 
 fn main() {
-    let input_file = fs::read_to_string("./inputs/19_linen_layout.txt").unwrap();
-    println!("{}", get_possible_pattern_count(&input_file));
-    println!("{}", get_total_arrangements(&input_file));
+    let runner = Runner::from_args("19_linen_layout");
+    let input_file = runner.read_input();
+    runner.run_part(1, &input_file, || get_possible_pattern_count(&input_file));
+    runner.run_part(2, &input_file, || get_total_arrangements(&input_file));
 }
 
 fn get_possible_pattern_count(input: &str) -> u64 {
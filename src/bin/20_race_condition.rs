@@ -222,12 +222,13 @@ Find the best cheats using the updated cheating rules. How many cheats
 would save you at least 100 picoseconds?
  */
 use std::cmp::max;
-use std::fs;
+use advent_of_code2024::runner::Runner;
 
 fn main() {
-    let input_file = fs::read_to_string("./inputs/20_race_condition.txt").unwrap();
-    println!("{}", get_cheat_count(&input_file, 100, 2));
-    println!("{}", get_cheat_count(&input_file, 100, 20));
+    let runner = Runner::from_args("20_race_condition");
+    let input_file = runner.read_input();
+    runner.run_part(1, &input_file, || get_cheat_count(&input_file, 100, 2));
+    runner.run_part(2, &input_file, || get_cheat_count(&input_file, 100, 20));
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
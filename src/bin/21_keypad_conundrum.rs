@@ -180,15 +180,19 @@ the complexities of the five codes on your list?
  */
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::fmt::{Display, Formatter};
-use std::fs;
+use advent_of_code2024::runner::Runner;
 use itertools::Itertools;
 
 fn main() {
-    let input_file = fs::read_to_string("./inputs/21_keypad_conundrum.txt").unwrap();
-    println!("{}", get_complexity_sum_bfs::<2>(&input_file));
-    println!("{}", get_complexity_sum_path_construction::<2>(&input_file));
+    let runner = Runner::from_args("21_keypad_conundrum");
+    let input_file = runner.read_input();
+    runner.run_part(1, &input_file, || get_complexity_sum_bfs::<2>(&input_file));
+    if runner.has_flag("--cross-check") {
+        // Part 1 again, the way part 2 has to be done
+        println!("{}", get_complexity_sum_path_construction::<2>(&input_file));
+    }
     // BFS solution is too slow for the size 25 problem
-    println!("{}", get_complexity_sum_path_construction::<25>(&input_file));
+    runner.run_part(2, &input_file, || get_complexity_sum_path_construction::<25>(&input_file));
 }
 
 trait Keypad {
@@ -196,12 +196,15 @@ same sequence of changes in every buyer's future prices, you get the most
 bananas in total. What is the most bananas you can get?
  */
 use std::collections::{HashMap, HashSet, VecDeque};
-use std::fs;
+use advent_of_code2024::runner::Runner;
 
 fn main() {
-    let input_file = fs::read_to_string("./inputs/22_monkey_market.txt").unwrap();
-    println!("{}", get_2000th_secret_number_sum(&input_file));
-    println!("{}", get_maximum_bananas(&input_file));
+    let runner = Runner::from_args("22_monkey_market").solver_version("2");
+    runner.run_parts(
+        get_2000th_secret_number_sum,
+        get_maximum_bananas,
+        |runner| get_secret_number_sum_and_maximum_bananas(runner.input_lines())
+    );
 }
 
 const fn mix(secret_number: u64, number: u64) -> u64 {
@@ -221,67 +224,81 @@ const fn iter_secret_number(mut secret_number: u64) -> u64 {
     secret_number
 }
 
-fn get_2000th_secret_number_sum(input: &str) -> u64 {
-    input.lines()
-        .map(|line| {
-            let initial_secret_number = line.parse::<u64>().unwrap();
+fn get_2000th_secret_number(initial_secret_number: u64) -> u64 {
+    let mut secret_number = initial_secret_number;
+    for _ in 0..2000 {
+        secret_number = iter_secret_number(secret_number);
+    }
 
-            let mut secret_number = initial_secret_number;
-            for _ in 0..2000 {
-                secret_number = iter_secret_number(secret_number);
-            }
+    secret_number
+}
 
-            secret_number
-        })
+fn get_2000th_secret_number_sum(input: &str) -> u64 {
+    input.lines()
+        .map(|line| get_2000th_secret_number(line.parse::<u64>().unwrap()))
         .sum()
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
 struct ChangeSequence([i8; 4]);
 
+/// Adds the bananas this buyer would sell for at the first occurrence of each change sequence
+fn add_buyer_bananas(initial_secret_number: u64, bananas_for_sequence: &mut HashMap<ChangeSequence, u64>) {
+    let mut seen_sequences = HashSet::new();
+
+    let mut secret_number = initial_secret_number;
+    let mut changes = VecDeque::with_capacity(4);
+    changes.push_back(None);
+    changes.push_back(None);
+    changes.push_back(None);
+    changes.push_back(None);
+    for _ in 0..2000 {
+        let old_price = (secret_number % 10) as i8;
+        secret_number = iter_secret_number(secret_number);
+        let new_price = (secret_number % 10) as i8;
+        changes.pop_front();
+        changes.push_back(Some(new_price - old_price));
+        if changes.iter().all(|x| x.is_some()) {
+            let sequence = ChangeSequence(
+                [
+                    changes[0].unwrap(),
+                    changes[1].unwrap(),
+                    changes[2].unwrap(),
+                    changes[3].unwrap(),
+                ]
+            );
+            if !seen_sequences.contains(&sequence) {
+                seen_sequences.insert(sequence);
+                bananas_for_sequence.insert(sequence, bananas_for_sequence.get(&sequence).cloned().unwrap_or_default() + (new_price as u64));
+            }
+        }
+    }
+}
+
 fn get_maximum_bananas(input: &str) -> u64 {
     // This probably isn't the fastest solution - it takes my machine ~8s in debug build (though
     // only ~0.5s in a release build)
     // But it is a pretty simple way to check all possible change sequences
     let mut bananas_for_sequence: HashMap<ChangeSequence, u64> = HashMap::new();
     input.lines()
-        .for_each(|line| {
-            let initial_secret_number = line.parse::<u64>().unwrap();
-
-            let mut seen_sequences = HashSet::new();
-
-            let mut secret_number = initial_secret_number;
-            let mut changes = VecDeque::with_capacity(4);
-            changes.push_back(None);
-            changes.push_back(None);
-            changes.push_back(None);
-            changes.push_back(None);
-            for _ in 0..2000 {
-                let old_price = (secret_number % 10) as i8;
-                secret_number = iter_secret_number(secret_number);
-                let new_price = (secret_number % 10) as i8;
-                changes.pop_front();
-                changes.push_back(Some(new_price - old_price));
-                if changes.iter().all(|x| x.is_some()) {
-                    let sequence = ChangeSequence(
-                        [
-                            changes[0].unwrap(),
-                            changes[1].unwrap(),
-                            changes[2].unwrap(),
-                            changes[3].unwrap(),
-                        ]
-                    );
-                    if !seen_sequences.contains(&sequence) {
-                        seen_sequences.insert(sequence);
-                        bananas_for_sequence.insert(sequence, bananas_for_sequence.get(&sequence).cloned().unwrap_or_default() + (new_price as u64));
-                    }
-                }
-            }
-        });
+        .for_each(|line| add_buyer_bananas(line.parse::<u64>().unwrap(), &mut bananas_for_sequence));
 
     bananas_for_sequence.values().max().cloned().unwrap()
 }
 
+/// Computes both parts in a single pass, a buyer at a time
+fn get_secret_number_sum_and_maximum_bananas(input_lines: impl Iterator<Item=impl AsRef<str>>) -> (u64, u64) {
+    let mut secret_number_sum = 0;
+    let mut bananas_for_sequence: HashMap<ChangeSequence, u64> = HashMap::new();
+    for line in input_lines {
+        let initial_secret_number = line.as_ref().parse::<u64>().unwrap();
+        secret_number_sum += get_2000th_secret_number(initial_secret_number);
+        add_buyer_bananas(initial_secret_number, &mut bananas_for_sequence);
+    }
+
+    (secret_number_sum, bananas_for_sequence.values().max().cloned().unwrap())
+}
+
 #[test]
 fn test_part1() {
     const SECRET_NUMBER_ITERS: &[u64] = &[
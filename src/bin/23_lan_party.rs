@@ -114,13 +114,14 @@ a bunch of nerds.) In this example, the password would be co,de,ka,ta.
 What is the password to get into the LAN party?
  */
 use std::collections::{HashMap, HashSet};
-use std::fs;
+use advent_of_code2024::runner::Runner;
 use itertools::Itertools;
 
 fn main() {
-    let input_file = fs::read_to_string("./inputs/23_lan_party.txt").unwrap();
-    println!("{}", get_count_3cliques_with_t_computer(&input_file));
-    println!("{}", get_lan_password(&input_file));
+    let runner = Runner::from_args("23_lan_party");
+    let input_file = runner.read_input();
+    runner.run_part(1, &input_file, || get_count_3cliques_with_t_computer(&input_file));
+    runner.run_part(2, &input_file, || get_lan_password(&input_file));
 }
 
 #[derive(Clone)]
@@ -266,16 +266,58 @@ use itertools::Itertools;
 use regex::Regex;
 use std::collections::{HashMap, HashSet};
 use std::fmt::{Display, Formatter};
-use std::fs;
+use advent_of_code2024::runner::Runner;
 use std::ops::Shl;
 use rayon::iter::{IntoParallelIterator, IntoParallelRefIterator};
 use rayon::iter::ParallelIterator;
 
 fn main() {
-    let input_file = fs::read_to_string("./inputs/24_crossed_wires.txt").unwrap();
-    println!("{}", get_decimal_z_output(&input_file));
+    let runner = Runner::from_args("24_crossed_wires");
+    let input_file = runner.read_input();
+    runner.run_part(1, &input_file, || get_decimal_z_output(&input_file));
 
-    let mut labels = get_all_labels(&input_file);
+    if runner.has_flag("--search") {
+        search_for_swaps(&input_file);
+    }
+
+    // The following four passed the check in search_for_swaps so lets see if this actually works
+
+    // wrm <-> wss
+    // thm <-> z08
+    // gbs <-> grd
+    // hwq <-> z22
+    //
+    // wrm <-> wss
+    // thm <-> z08
+    // gbs <-> grd
+    // fjs <-> z22
+    //
+    // wrm <-> wss
+    // thm <-> z08
+    // gbs <-> z29
+    // hwq <-> z22
+    //
+    // wrm <-> wss
+    // thm <-> z08
+    // gbs <-> z29
+    // fjs <-> z22
+
+    // Sort the results
+    // This one passed!
+
+    let mut results = vec![
+        "wrm", "wss",
+        "thm", "z08",
+        "gbs", "z29",
+        "hwq", "z22",
+    ];
+    results.sort();
+    runner.run_part(2, &input_file, || results.iter().join(","));
+}
+
+/// The search which found the swaps for part 2, printing its progress as it goes
+fn search_for_swaps(input_file: &str) {
+    let mut labels = get_all_labels(input_file);
     labels.sort();
 
     // There are four wrong outputs and four things that need to be swapped so I suspect each
@@ -290,7 +332,7 @@ fn main() {
             for i in 0..=44 {
                 let y = 1 << i;
                 let x = 0;
-                let z = get_decimal_z_output_x_y(&input_file, x, y, &swaps);
+                let z = get_decimal_z_output_x_y(input_file, x, y, &swaps);
                 let expected_z = x + y;
                 if z != expected_z {
                     wrong_count += 1;
@@ -339,7 +381,7 @@ fn main() {
                 let y = 1 << i;
                 for j in 0..=44 {
                     let x = 1 << j;
-                    let z = get_decimal_z_output_x_y(&input_file, x, y, &swaps);
+                    let z = get_decimal_z_output_x_y(input_file, x, y, &swaps);
                     let expected_z = x + y;
                     if z != expected_z {
                         wrong_count += 1;
@@ -355,40 +397,6 @@ fn main() {
                 println!("{} <-> {}", swaps[3].0, swaps[3].1);
             }
     });
-
-    // The following four passed the above check so lets see if this actually works
-
-    // wrm <-> wss
-    // thm <-> z08
-    // gbs <-> grd
-    // hwq <-> z22
-    //
-    // wrm <-> wss
-    // thm <-> z08
-    // gbs <-> grd
-    // fjs <-> z22
-    //
-    // wrm <-> wss
-    // thm <-> z08
-    // gbs <-> z29
-    // hwq <-> z22
-    //
-    // wrm <-> wss
-    // thm <-> z08
-    // gbs <-> z29
-    // fjs <-> z22
-
-    // Sort the results
-    // This one passed!
-
-    let mut results = vec![
-        "wrm", "wss",
-        "thm", "z08",
-        "gbs", "z29",
-        "hwq", "z22",
-    ];
-    results.sort();
-    println!("{}", results.iter().join(","));
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Hash, Ord, PartialOrd)]
@@ -0,0 +1,40 @@
+//! The puzzle inputs baked into the binaries by the `embed-inputs` feature, so that they can be run
+//! from outside the repository root
+
+macro_rules! embed_inputs {
+    ($($day:literal),* $(,)?) => {
+        pub fn embedded_input(day: &str) -> Option<&'static str> {
+            match day {
+                $($day => Some(include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/inputs/", $day, ".txt"))),)*
+                _ => None,
+            }
+        }
+    };
+}
+
+embed_inputs!(
+    "01_historian_hysteria",
+    "02_red_nosed_reports",
+    "03_mull_it_over",
+    "04_ceres_search",
+    "05_print_queue",
+    "06_guard_gallivant",
+    "07_bridge_repair",
+    "08_resonant_collinearity",
+    "09_disk_fragmenter",
+    "10_hoof_it",
+    "11_plutonian_pebbles",
+    "12_garden_groups",
+    "13_claw_contraption",
+    "14_restroom_redoubt",
+    "15_warehouse_woes",
+    "16_reindeer_maze",
+    "17_chronospatial_computer",
+    "18_ram_run",
+    "19_linen_layout",
+    "20_race_condition",
+    "21_keypad_conundrum",
+    "22_monkey_market",
+    "23_lan_party",
+    "24_crossed_wires",
+);
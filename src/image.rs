@@ -0,0 +1,104 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use crate::visualise::Grid;
+
+pub type Rgb = [u8; 3];
+
+/// Writes a binary (P6) PPM image, the pixels are in row-major order
+pub fn write_ppm(writer: &mut impl Write, width: usize, height: usize, pixels: impl IntoIterator<Item = Rgb>) -> io::Result<()> {
+    write!(writer, "P6\n{width} {height}\n255\n")?;
+    for pixel in pixels {
+        writer.write_all(&pixel)?;
+    }
+    Ok(())
+}
+
+/// Writes a binary (P5) PGM image, the pixels are in row-major order
+pub fn write_pgm(writer: &mut impl Write, width: usize, height: usize, pixels: impl IntoIterator<Item = u8>) -> io::Result<()> {
+    write!(writer, "P5\n{width} {height}\n255\n")?;
+    for pixel in pixels {
+        writer.write_all(&[pixel])?;
+    }
+    Ok(())
+}
+
+/// Each tile of the grid as `scale` * `scale` pixels, in row-major order
+fn scaled_pixels<G: Grid, P: Copy>(grid: &G, scale: usize, pixel: impl Fn(G::Tile) -> P) -> Vec<P> {
+    let mut pixels = Vec::with_capacity(grid.width() * grid.height() * scale * scale);
+    for y in 0..grid.height() {
+        let row = (0..grid.width())
+            .flat_map(|x| std::iter::repeat_n(pixel(grid.tile(x, y)), scale))
+            .collect::<Vec<_>>();
+        for _ in 0..scale {
+            pixels.extend_from_slice(&row);
+        }
+    }
+    pixels
+}
+
+/// Saves the grid as a colour image with each tile drawn as a `scale` * `scale` square
+pub fn save_grid_ppm<G: Grid>(path: impl AsRef<Path>, grid: &G, scale: usize, colour: impl Fn(G::Tile) -> Rgb) -> io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_ppm(&mut writer, grid.width() * scale, grid.height() * scale, scaled_pixels(grid, scale, colour))?;
+    writer.flush()
+}
+
+/// Saves the grid as a greyscale image with each tile drawn as a `scale` * `scale` square
+pub fn save_grid_pgm<G: Grid>(path: impl AsRef<Path>, grid: &G, scale: usize, shade: impl Fn(G::Tile) -> u8) -> io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_pgm(&mut writer, grid.width() * scale, grid.height() * scale, scaled_pixels(grid, scale, shade))?;
+    writer.flush()
+}
+
+/// The path of the `index`th image of a numbered sequence, e.g. `frames/robots_0042.pgm`
+pub fn sequence_path(prefix: &str, index: usize, extension: &str) -> String {
+    format!("{prefix}_{index:04}.{extension}")
+}
+
+/// A colour for an id which is easy to tell apart from the colours of nearby ids
+///
+/// The hue steps round the colour wheel by the golden angle so consecutive ids never look alike.
+pub fn distinct_colour(id: usize) -> Rgb {
+    let hue = (id as f64 * 137.507_764) % 360.0;
+    // HSV to RGB with full saturation and value
+    let sector = hue / 60.0;
+    let rising = (255.0 * sector.fract()) as u8;
+    let falling = 255 - rising;
+    match sector as u8 {
+        0 => [255, rising, 0],
+        1 => [falling, 255, 0],
+        2 => [0, 255, rising],
+        3 => [0, falling, 255],
+        4 => [rising, 0, 255],
+        _ => [255, 0, falling],
+    }
+}
+
+#[test]
+fn test_write_pgm() {
+    struct Diagonal;
+
+    impl Grid for Diagonal {
+        type Tile = bool;
+
+        fn width(&self) -> usize {
+            2
+        }
+
+        fn height(&self) -> usize {
+            2
+        }
+
+        fn tile(&self, x: usize, y: usize) -> bool {
+            x == y
+        }
+    }
+
+    let mut image = vec![];
+    write_pgm(&mut image, 4, 4, scaled_pixels(&Diagonal, 2, |tile| if tile { 255 } else { 0 })).unwrap();
+    assert_eq!(
+        b"P5\n4 4\n255\n\xff\xff\x00\x00\xff\xff\x00\x00\x00\x00\xff\xff\x00\x00\xff\xff".as_slice(),
+        image.as_slice()
+    );
+}
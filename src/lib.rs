@@ -1,2 +1,8 @@
-
+pub mod answer_cache;
 pub mod dfs;
+#[cfg(feature = "embed-inputs")]
+pub mod embedded_inputs;
+pub mod image;
+pub mod precedence;
+pub mod runner;
+pub mod visualise;
@@ -0,0 +1,157 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::iter;
+use std::num::ParseIntError;
+use std::str::FromStr;
+use crate::dfs::{depth_first_search, DfsError};
+
+/// A set of `before|after` rules saying which items have to come before which others
+///
+/// The rules don't have to mention every pair of items, or even be consistent, the checked methods
+/// work for any rules while the fast ones rely on the rules forming a total order over the items
+/// they're given.
+#[derive(Clone, Debug, Default)]
+pub struct PrecedenceRules {
+    pairs: HashSet<(u64, u64)>,
+    successors: HashMap<u64, Vec<u64>>,
+}
+
+impl PrecedenceRules {
+    pub fn new() -> Self {
+        PrecedenceRules::default()
+    }
+
+    pub fn add(&mut self, before: u64, after: u64) {
+        if self.pairs.insert((before, after)) {
+            self.successors.entry(before).or_default().push(after);
+        }
+    }
+
+    /// Parses `before|after` lines, stopping after the first line which isn't a rule (e.g. the
+    /// blank line before whatever follows the rules)
+    pub fn parse_lines<'a>(lines: &mut impl Iterator<Item = &'a str>) -> Result<Self, ParseIntError> {
+        let mut rules = PrecedenceRules::new();
+        for line in lines {
+            let Some((before, after)) = line.split_once('|') else { break; };
+            rules.add(before.parse()?, after.parse()?);
+        }
+        Ok(rules)
+    }
+
+    pub fn must_precede(&self, before: u64, after: u64) -> bool {
+        self.pairs.contains(&(before, after))
+    }
+
+    /// Everything that has to come after the item
+    pub fn successors(&self, before: u64) -> &[u64] {
+        self.successors.get(&before).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn compare(&self, a: u64, b: u64) -> Ordering {
+        if self.must_precede(a, b) {
+            Ordering::Less
+        } else if self.must_precede(b, a) {
+            Ordering::Greater
+        } else {
+            Ordering::Equal
+        }
+    }
+
+    /// Whether the rules order every pair of distinct items without any cycles, which the fast
+    /// methods need to be exact
+    pub fn is_total_over(&self, items: &[u64]) -> bool {
+        let distinct_items = items.iter().copied().collect::<HashSet<_>>();
+        // With a rule between every pair, there's no cycle exactly when each item has to come
+        // before a different number of the others
+        let mut successor_counts = HashSet::new();
+        distinct_items.iter().all(|&item| {
+            let successor_count = distinct_items.iter().filter(|&&after| self.must_precede(item, after)).count();
+            let predecessor_count = distinct_items.iter().filter(|&&before| self.must_precede(before, item)).count();
+            successor_count + predecessor_count == distinct_items.len() - 1 && successor_counts.insert(successor_count)
+        })
+    }
+
+    /// Checks the order in O(n) by only looking at neighbouring items, which is exact when the
+    /// rules are a total order over the items
+    pub fn is_ordered(&self, items: &[u64]) -> bool {
+        items.windows(2).all(|pair| !self.must_precede(pair[1], pair[0]))
+    }
+
+    /// Checks every pair of items, so is exact whatever the rules are
+    pub fn is_ordered_checked(&self, items: &[u64]) -> bool {
+        items.iter()
+            .enumerate()
+            .all(|(i, &before)| items[(i + 1)..].iter().all(|&after| !self.must_precede(after, before)))
+    }
+
+    /// Sorts the items with the rules as a comparator, which is only valid when the rules are a
+    /// total order over the items
+    pub fn sort(&self, items: &mut [u64]) {
+        items.sort_by(|&a, &b| self.compare(a, b));
+    }
+
+    /// Sorts the items into an order which breaks none of the rules between them, whatever the
+    /// rules are, failing if they form a cycle so that there is no such order
+    pub fn checked_sort(&self, items: &mut [u64]) -> Result<(), DfsError> {
+        // The search visits each item once, so sort the distinct items and then put back every copy
+        let mut counts: HashMap<u64, usize> = HashMap::new();
+        for &item in items.iter() {
+            *counts.entry(item).or_default() += 1;
+        }
+        let distinct_items = counts.keys().copied().collect::<Vec<_>>();
+        let mut sorted = Vec::with_capacity(distinct_items.len());
+        depth_first_search(
+            &distinct_items,
+            |&item| self.successors(item).iter().filter(|after| counts.contains_key(after)),
+            |&item| sorted.push(item)
+        )?;
+        // The search visits each item after everything which has to come after it
+        let sorted = sorted.into_iter().rev().flat_map(|item| iter::repeat_n(item, counts[&item]));
+        for (item, sorted_item) in items.iter_mut().zip(sorted) {
+            *item = sorted_item;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for PrecedenceRules {
+    type Err = ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        PrecedenceRules::parse_lines(&mut s.lines())
+    }
+}
+
+#[test]
+fn test_precedence_rules() {
+    let rules: PrecedenceRules = "1|2\n2|3\n1|3".parse().unwrap();
+    assert!(rules.is_ordered(&[1, 2, 3]));
+    assert!(!rules.is_ordered(&[2, 1, 3]));
+
+    let mut items = [3, 1, 2];
+    rules.sort(&mut items);
+    assert_eq!([1, 2, 3], items);
+
+    // 2 isn't ordered against the others, so the broken rule between 1 and 3 is missed
+    let partial_rules: PrecedenceRules = "1|3".parse().unwrap();
+    assert!(partial_rules.is_ordered(&[3, 2, 1]));
+    assert!(!partial_rules.is_ordered_checked(&[3, 2, 1]));
+    let mut items = [3, 2, 1];
+    partial_rules.checked_sort(&mut items).unwrap();
+    assert!(partial_rules.is_ordered_checked(&items));
+
+    // Repeated items all end up in place
+    let mut items = [1, 2, 1];
+    rules.checked_sort(&mut items).unwrap();
+    assert_eq!([1, 1, 2], items);
+    let mut items = [3, 2, 1, 3, 1];
+    rules.checked_sort(&mut items).unwrap();
+    assert_eq!([1, 1, 2, 3, 3], items);
+
+    assert!(rules.is_total_over(&[3, 1, 2]));
+    assert!(!partial_rules.is_total_over(&[3, 2, 1]));
+
+    let cyclic_rules: PrecedenceRules = "1|2\n2|3\n3|1".parse().unwrap();
+    assert!(!cyclic_rules.is_total_over(&[1, 2, 3]));
+    assert!(cyclic_rules.checked_sort(&mut [1, 2, 3]).is_err());
+}
@@ -0,0 +1,208 @@
+use std::env;
+use std::fmt::Display;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Read};
+use std::str::FromStr;
+use std::time::Duration;
+use crate::answer_cache::{AnswerCache, CacheKey};
+use crate::visualise::{Legend, Renderer};
+#[cfg(feature = "embed-inputs")]
+use crate::embedded_inputs::embedded_input;
+
+/// Where the puzzle input is read from
+enum InputSource {
+    Stdin,
+    File(String),
+    /// Baked into the binary by the `embed-inputs` feature
+    #[cfg(feature = "embed-inputs")]
+    Embedded(&'static str),
+}
+
+/// The shared `main` scaffolding for each day's binary
+///
+/// Usage: `<day binary> [INPUT] [--no-cache]`
+///
+/// `INPUT` is a path to the puzzle input, or `-` to read it from stdin. It defaults to
+/// `./inputs/<day>.txt`, or to the copy of that file embedded in the binary when built with the
+/// `embed-inputs` feature.
+///
+/// Answers are looked up in the [AnswerCache] before being computed, so re-running a day whose
+/// input and solver haven't changed prints instantly. `--no-cache` forces recomputation (the fresh
+/// answer still replaces whatever was cached).
+///
+/// Days which can animate their simulations take `--visualise`, with the playback controlled by
+/// `--frame-delay=<ms>` and `--frame-step=<n>` (only draw every nth frame). Days which can save
+/// their grids as images take `--export=<path>` (and/or `--export-sequence=<prefix>` for numbered
+/// frames), with each tile drawn as a `--scale=<n>` pixel square.
+pub struct Runner {
+    day: &'static str,
+    args: Vec<String>,
+    cache: AnswerCache,
+    solver_version: Option<&'static str>,
+}
+
+impl Runner {
+    pub fn from_args(day: &'static str) -> Self {
+        let args = env::args().skip(1).collect::<Vec<_>>();
+        Runner {
+            day,
+            args,
+            cache: AnswerCache::default(),
+            solver_version: None,
+        }
+    }
+
+    /// Also key cached answers by this version. Bump it whenever a day's solver is rewritten, so
+    /// that answers cached by the old solver can't hide a regression in the new one.
+    pub fn solver_version(mut self, solver_version: &'static str) -> Self {
+        self.solver_version = Some(solver_version);
+        self
+    }
+
+    /// Whether a `--name` flag was passed
+    pub fn has_flag(&self, name: &str) -> bool {
+        self.args.iter().any(|arg| arg == name)
+    }
+
+    /// The value of a `--name=value` option
+    pub fn option(&self, name: &str) -> Option<&str> {
+        self.args.iter()
+            .find_map(|arg| arg.strip_prefix(name)?.strip_prefix('='))
+    }
+
+    /// The value of a `--name=value` option parsed as a `T`
+    pub fn parsed_option<T: FromStr>(&self, name: &str) -> Option<T> {
+        self.option(name)
+            .map(|value| value.parse().unwrap_or_else(|_| panic!("Couldn't parse {name}={value}")))
+    }
+
+    /// A renderer for `--visualise`, with the playback configured from the command line
+    pub fn renderer<T: Copy + PartialEq>(&self, legend: Legend<T>) -> Renderer<T> {
+        let mut renderer = Renderer::new(legend);
+        if let Some(frame_delay) = self.parsed_option("--frame-delay") {
+            renderer = renderer.frame_delay(Duration::from_millis(frame_delay));
+        }
+        if let Some(frame_step) = self.parsed_option("--frame-step") {
+            renderer = renderer.frame_step(frame_step);
+        }
+        renderer
+    }
+
+    /// The size in pixels of each tile in exported images
+    pub fn export_scale(&self) -> usize {
+        self.parsed_option("--scale").unwrap_or(1)
+    }
+
+    /// The first argument that isn't a `--flag`, or the day's own input
+    fn input_source(&self) -> InputSource {
+        match self.args.iter().find(|arg| !arg.starts_with("--")) {
+            Some(arg) if arg == "-" => InputSource::Stdin,
+            Some(path) => InputSource::File(path.clone()),
+            #[cfg(feature = "embed-inputs")]
+            None => InputSource::Embedded(
+                embedded_input(self.day).unwrap_or_else(|| panic!("No input embedded for {}", self.day))
+            ),
+            #[cfg(not(feature = "embed-inputs"))]
+            None => InputSource::File(format!("./inputs/{}.txt", self.day)),
+        }
+    }
+
+    /// Whether the input is being piped in, in which case it can only be read once
+    pub fn is_stdin_input(&self) -> bool {
+        matches!(self.input_source(), InputSource::Stdin)
+    }
+
+    pub fn read_input(&self) -> String {
+        match self.input_source() {
+            InputSource::Stdin => {
+                let mut input = String::new();
+                io::stdin().read_to_string(&mut input).unwrap();
+                input
+            }
+            InputSource::File(path) => fs::read_to_string(path).unwrap(),
+            #[cfg(feature = "embed-inputs")]
+            InputSource::Embedded(input) => input.to_string(),
+        }
+    }
+
+    /// Reads the input a line at a time rather than holding all of it in memory
+    pub fn input_lines(&self) -> impl Iterator<Item = String> {
+        self.input_reader().lines().map(|line| line.unwrap())
+    }
+
+    /// The input as a stream, for days which don't need all of it at once
+    pub fn input_reader(&self) -> Box<dyn BufRead> {
+        match self.input_source() {
+            InputSource::Stdin => Box::new(io::stdin().lock()),
+            InputSource::File(path) => Box::new(BufReader::new(File::open(path).unwrap())),
+            #[cfg(feature = "embed-inputs")]
+            InputSource::Embedded(input) => Box::new(input.as_bytes()),
+        }
+    }
+
+    /// Prints the answer to one part, reusing the cached answer for this input if there is one
+    pub fn run_part<T: Display>(&self, part: u8, input: &str, solve: impl FnOnce() -> T) {
+        let key = CacheKey::new(self.day, part, input.as_bytes(), self.solver_version);
+        if !self.has_flag("--no-cache") {
+            if let Some(answer) = self.cache.get(&key) {
+                println!("{answer}");
+                return;
+            }
+        }
+
+        let answer = solve().to_string();
+        if let Err(e) = self.cache.store(&key, &answer) {
+            eprintln!("Couldn't cache the answer to part {part}: {e}");
+        }
+        println!("{answer}");
+    }
+
+    /// Prints the answers to both parts with [Runner::run_part], unless the input is being piped
+    /// in, in which case `single_pass` answers both as it reads the input
+    ///
+    /// Call this after handling a day's own options, which would otherwise never see piped input.
+    pub fn run_parts<A: Display, B: Display>(
+        &self,
+        part1: impl FnOnce(&str) -> A,
+        part2: impl FnOnce(&str) -> B,
+        single_pass: impl FnOnce(&Self) -> (A, B)
+    ) {
+        if self.is_stdin_input() {
+            let (answer1, answer2) = single_pass(self);
+            println!("{answer1}");
+            println!("{answer2}");
+            return;
+        }
+
+        let input = self.read_input();
+        self.run_part(1, &input, || part1(&input));
+        self.run_part(2, &input, || part2(&input));
+    }
+}
+
+#[test]
+fn test_solver_version_misses_cache() {
+    let cache_dir = env::temp_dir().join(format!("runner_test_{}", std::process::id()));
+    let runner = |solver_version| Runner {
+        day: "00_test",
+        args: vec![],
+        cache: AnswerCache::new(&cache_dir),
+        solver_version,
+    };
+    let solve_count = std::cell::Cell::new(0);
+    let solve = || {
+        solve_count.set(solve_count.get() + 1);
+        42
+    };
+
+    runner(Some("1")).run_part(1, "input", solve);
+    runner(Some("1")).run_part(1, "input", solve);
+    assert_eq!(1, solve_count.get());
+    // A new solver version has to work the answer out again
+    runner(Some("2")).run_part(1, "input", solve);
+    assert_eq!(2, solve_count.get());
+    runner(None).run_part(1, "input", solve);
+    assert_eq!(3, solve_count.get());
+
+    fs::remove_dir_all(&cache_dir).unwrap();
+}
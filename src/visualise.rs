@@ -0,0 +1,218 @@
+use std::io::{self, Write};
+use std::thread::sleep;
+use std::time::Duration;
+
+/// Anything laid out on a rectangular grid that can be drawn a tile at a time
+pub trait Grid {
+    type Tile: Copy + PartialEq;
+
+    fn width(&self) -> usize;
+    fn height(&self) -> usize;
+    fn tile(&self, x: usize, y: usize) -> Self::Tile;
+}
+
+impl<G: Grid> Grid for &G {
+    type Tile = G::Tile;
+
+    fn width(&self) -> usize {
+        (*self).width()
+    }
+
+    fn height(&self) -> usize {
+        (*self).height()
+    }
+
+    fn tile(&self, x: usize, y: usize) -> Self::Tile {
+        (*self).tile(x, y)
+    }
+}
+
+/// The standard ANSI terminal foreground colours
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Colour {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    BrightBlack,
+}
+
+impl Colour {
+    fn ansi_code(self) -> u8 {
+        match self {
+            Colour::Black => 30,
+            Colour::Red => 31,
+            Colour::Green => 32,
+            Colour::Yellow => 33,
+            Colour::Blue => 34,
+            Colour::Magenta => 35,
+            Colour::Cyan => 36,
+            Colour::White => 37,
+            Colour::BrightBlack => 90,
+        }
+    }
+}
+
+struct LegendEntry<T> {
+    tile: T,
+    symbol: char,
+    colour: Colour,
+    description: &'static str,
+}
+
+/// How each type of tile is drawn
+pub struct Legend<T> {
+    entries: Vec<LegendEntry<T>>,
+}
+
+impl<T: Copy + PartialEq> Legend<T> {
+    pub fn new() -> Self {
+        Legend { entries: vec![] }
+    }
+
+    pub fn with(mut self, tile: T, symbol: char, colour: Colour, description: &'static str) -> Self {
+        self.entries.push(LegendEntry {
+            tile,
+            symbol,
+            colour,
+            description,
+        });
+        self
+    }
+
+    fn get(&self, tile: T) -> Option<&LegendEntry<T>> {
+        self.entries.iter().find(|entry| entry.tile == tile)
+    }
+}
+
+impl<T: Copy + PartialEq> Default for Legend<T> {
+    fn default() -> Self {
+        Legend::new()
+    }
+}
+
+/// Draws grid states to the terminal, optionally as an animation
+///
+/// Each call to [Renderer::frame] is one frame of the animation. Only every `frame_step`th frame
+/// is actually drawn (so that long simulations can be sped through) and each drawn frame is held
+/// for `frame_delay`.
+pub struct Renderer<T> {
+    legend: Legend<T>,
+    frame_delay: Duration,
+    frame_step: usize,
+    frame_count: usize,
+}
+
+impl<T: Copy + PartialEq> Renderer<T> {
+    pub fn new(legend: Legend<T>) -> Self {
+        Renderer {
+            legend,
+            frame_delay: Duration::from_millis(50),
+            frame_step: 1,
+            frame_count: 0,
+        }
+    }
+
+    pub fn frame_delay(mut self, frame_delay: Duration) -> Self {
+        self.frame_delay = frame_delay;
+        self
+    }
+
+    pub fn frame_step(mut self, frame_step: usize) -> Self {
+        self.frame_step = frame_step.max(1);
+        self
+    }
+
+    /// Renders the grid as coloured text, tiles missing from the legend are drawn as `?`
+    pub fn render(&self, grid: &impl Grid<Tile = T>) -> String {
+        let mut result = String::new();
+        let mut current_colour = None;
+        for y in 0..grid.height() {
+            for x in 0..grid.width() {
+                let (symbol, colour) = match self.legend.get(grid.tile(x, y)) {
+                    Some(entry) => (entry.symbol, Some(entry.colour)),
+                    None => ('?', None),
+                };
+                // Only emit an escape code when the colour actually changes
+                if colour != current_colour {
+                    match colour {
+                        Some(colour) => result.push_str(&format!("\x1b[{}m", colour.ansi_code())),
+                        None => result.push_str("\x1b[0m"),
+                    }
+                    current_colour = colour;
+                }
+                result.push(symbol);
+            }
+            result.push('\n');
+        }
+        result.push_str("\x1b[0m");
+        result
+    }
+
+    /// Entries without a description (e.g. the other orientations of the same thing) are left out
+    fn render_legend(&self) -> String {
+        self.legend.entries.iter()
+            .filter(|entry| !entry.description.is_empty())
+            .map(|entry| format!("\x1b[{}m{}\x1b[0m {}", entry.colour.ansi_code(), entry.symbol, entry.description))
+            .collect::<Vec<_>>()
+            .join("  ")
+    }
+
+    /// Clears the terminal and draws the grid with the legend underneath
+    pub fn draw(&self, grid: &impl Grid<Tile = T>) {
+        let mut stdout = io::stdout().lock();
+        write!(stdout, "\x1b[H\x1b[2J{}\n{}\n", self.render(grid), self.render_legend()).unwrap();
+        stdout.flush().unwrap();
+    }
+
+    /// Advances the animation by one frame, the grid is only built if this frame will be drawn
+    pub fn frame_with<G: Grid<Tile = T>>(&mut self, make_grid: impl FnOnce() -> G) {
+        let is_drawn = self.frame_count.is_multiple_of(self.frame_step);
+        self.frame_count += 1;
+        if is_drawn {
+            self.draw(&make_grid());
+            sleep(self.frame_delay);
+        }
+    }
+
+    pub fn frame(&mut self, grid: &impl Grid<Tile = T>) {
+        self.frame_with(|| grid)
+    }
+
+    /// Draws the end state of the animation, whether or not it falls on a `frame_step`
+    pub fn final_frame(&mut self, grid: &impl Grid<Tile = T>) {
+        self.frame_count += 1;
+        self.draw(grid);
+    }
+}
+
+#[test]
+fn test_render() {
+    struct Checkerboard;
+
+    impl Grid for Checkerboard {
+        type Tile = bool;
+
+        fn width(&self) -> usize {
+            3
+        }
+
+        fn height(&self) -> usize {
+            2
+        }
+
+        fn tile(&self, x: usize, y: usize) -> bool {
+            (x + y).is_multiple_of(2)
+        }
+    }
+
+    let renderer = Renderer::new(Legend::new().with(true, '#', Colour::Red, "black square"));
+    assert_eq!(
+        "\x1b[31m#\x1b[0m?\x1b[31m#\n\x1b[0m?\x1b[31m#\x1b[0m?\n\x1b[0m",
+        renderer.render(&Checkerboard)
+    );
+}